@@ -0,0 +1,71 @@
+use anyhow::Result;
+use elasticsearch::indices::IndicesGetAliasParts;
+use serde_json::{json, Value};
+
+use crate::index_builder::{check_response, with_request_timeout, IndexBuilder};
+
+impl IndexBuilder {
+    /// adds the index to the alias `alias`. If `is_write_index` is set, all documents
+    /// which are imported through the alias will be written into this index.
+    pub async fn attach_to_alias(&self, alias: &str, is_write_index: bool) -> Result<()> {
+        self.update_aliases(json!([{
+            "add": {
+                "index": self.index_name(),
+                "alias": alias,
+                "is_write_index": is_write_index
+            }
+        }]))
+        .await?;
+        log::info!("added index '{}' to alias '{alias}'", self.index_name());
+        Ok(())
+    }
+
+    /// removes the index from the alias `alias`
+    pub async fn detach_from_alias(&self, alias: &str) -> Result<()> {
+        self.update_aliases(json!([{
+            "remove": {
+                "index": self.index_name(),
+                "alias": alias
+            }
+        }]))
+        .await?;
+        log::info!("removed index '{}' from alias '{alias}'", self.index_name());
+        Ok(())
+    }
+
+    /// returns the names of all aliases the index belongs to
+    pub async fn aliases(&self) -> Result<Vec<String>> {
+        let client = self.create_client()?;
+        let index_names = [self.index_name()];
+        let indices = client.indices();
+        let request = indices.get_alias(IndicesGetAliasParts::Index(&index_names));
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "reading aliases").await?;
+        let body: Value = response.json().await?;
+        let mut aliases: Vec<String> = body
+            .as_object()
+            .into_iter()
+            .flat_map(|indices| indices.values())
+            .filter_map(|index| index["aliases"].as_object())
+            .flat_map(|aliases| aliases.keys().cloned())
+            .collect();
+        aliases.sort();
+        aliases.dedup();
+        Ok(aliases)
+    }
+
+    async fn update_aliases(&self, actions: Value) -> Result<()> {
+        let client = self.create_client()?;
+        let indices = client.indices();
+        let request = indices
+            .update_aliases()
+            .body(json!({ "actions": actions }));
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        check_response(response, "updating aliases").await?;
+        Ok(())
+    }
+}
@@ -1,29 +1,378 @@
-use clap::Parser;
-use crate::Protocol;
+use chrono::{DateTime, Duration, FixedOffset};
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use elasticsearch::http::Url;
+use chrono_tz::Tz;
+use es4forensics::{
+    AmbiguousTime, BulkMode, ClockOffset, ExportFormat, InputFormat, NonexistentTime, Protocol,
+    TimestampFormat,
+};
 
 #[cfg(feature = "gzip")]
 const INPUTFILE_HELP: &str = "path to input file or '-' for stdin (files ending with .gz will be treated as being gzipped)";
 #[cfg(not(feature = "gzip"))]
 const INPUTFILE_HELP: &str = "path to input file or '-' for stdin";
 
+#[derive(clap::ValueEnum, Clone)]
+pub (crate) enum IdStrategyName {
+    /// hash over the whole document
+    ContentHash,
+
+    /// hash over the fields given with `--id-fields`
+    FieldHash,
+
+    /// let elasticsearch generate ids
+    Auto,
+
+    /// use the value of the field given with `--id-fields`
+    FromField,
+}
+
+#[derive(clap::Args)]
+pub (crate) struct KibanaArgs {
+    /// URL of the Kibana server [default: <PROTOCOL>://<HOST>:5601]
+    #[clap(long("kibana-url"), value_name("URL"))]
+    pub (crate) url: Option<Url>,
+
+    /// Kibana space to create the saved objects in
+    #[clap(long("kibana-space"), value_name("SPACE"))]
+    pub (crate) space: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub (crate) struct ParserArgs {
+    /// format of the input
+    #[clap(short('F'), long("format"), value_enum, default_value_t=InputFormat::Json)]
+    pub (crate) format: InputFormat,
+
+    /// timezone of local timestamps in the input
+    #[clap(long("timezone"), value_parser=parse_timezone, default_value="UTC")]
+    pub (crate) timezone: Tz,
+
+    /// how to resolve local timestamps which occur twice, at the end of daylight saving time
+    #[clap(long("ambiguous-time"), value_enum, default_value_t=AmbiguousTime::Earliest)]
+    pub (crate) ambiguous_time: AmbiguousTime,
+
+    /// how to resolve local timestamps which do not exist, at the beginning of daylight saving time
+    #[clap(long("nonexistent-time"), value_enum, default_value_t=NonexistentTime::Error)]
+    pub (crate) nonexistent_time: NonexistentTime,
+
+    /// JSON file which maps plaso attributes of specific data types to ECS fields
+    #[clap(long("plaso-mapping"), value_name("FILE"))]
+    pub (crate) plaso_mapping: Option<String>,
+
+    /// offset which is added to all timestamps of the evidence host, e.g. '-00:03:12' for a
+    /// clock which was 3 minutes and 12 seconds fast
+    #[clap(long("clock-offset"), value_name("[+-]HH:MM:SS"), allow_hyphen_values(true))]
+    pub (crate) clock_offset: Option<ClockOffset>,
+
+    /// JSON file with clock offsets per host name, like {"dc01": "-00:03:12"}, which take
+    /// precedence over '--clock-offset'. They only apply to documents with a 'host.name' (like
+    /// those of l2tcsv and json_line input); bodyfile and mactime documents have no host name
+    #[clap(long("clock-offsets"), value_name("FILE"))]
+    pub (crate) clock_offsets: Option<String>,
+
+    /// format of the timestamps in generated documents (use 'epoch-millis' for indices
+    /// which have been created by older versions)
+    #[clap(long("timestamp-format"), value_enum, default_value_t=TimestampFormat::Iso8601)]
+    pub (crate) timestamp_format: TimestampFormat,
+
+    #[clap(flatten)]
+    pub (crate) filter: FilterArgs,
+}
+
+#[derive(clap::Args)]
+pub (crate) struct FilterArgs {
+    /// skip documents which are older than this (RFC 3339 format)
+    #[clap(long("from"), value_parser=parse_rfc3339)]
+    pub (crate) from: Option<DateTime<FixedOffset>>,
+
+    /// skip documents which are newer than this (RFC 3339 format)
+    #[clap(long("to"), value_parser=parse_rfc3339)]
+    pub (crate) to: Option<DateTime<FixedOffset>>,
+
+    /// keep only documents whose file.path matches this glob pattern (can be repeated)
+    #[clap(long("include-path"), value_name("GLOB"))]
+    pub (crate) include_paths: Vec<String>,
+
+    /// keep only documents whose file.path matches this regular expression (can be repeated)
+    #[clap(long("include-path-regex"), value_name("REGEX"))]
+    pub (crate) include_path_regexes: Vec<String>,
+
+    /// skip documents whose file.path matches this glob pattern (can be repeated)
+    #[clap(long("exclude-path"), value_name("GLOB"))]
+    pub (crate) exclude_paths: Vec<String>,
+
+    /// skip documents whose file.path matches this regular expression (can be repeated)
+    #[clap(long("exclude-path-regex"), value_name("REGEX"))]
+    pub (crate) exclude_path_regexes: Vec<String>,
+
+    /// skip bodyfile and mactime entries whose timestamps are all unset (-1 or 0)
+    #[clap(long("skip-empty-timestamps"))]
+    pub (crate) skip_empty_timestamps: bool,
+}
+
 #[derive(clap::Subcommand)]
 pub (crate) enum Action {
     // create a new index
-    CreateIndex,
+    CreateIndex {
+        /// also create a Kibana data view and a saved search for the new index
+        #[clap(long("data-view"))]
+        data_view: bool,
+
+        /// add the new index to this alias (can be used multiple times)
+        #[clap(long("alias"))]
+        aliases: Vec<String>,
+
+        /// add the new index to this alias and make it the write index of the alias
+        #[clap(long("write-alias"))]
+        write_aliases: Vec<String>,
+
+        #[clap(flatten)]
+        kibana: KibanaArgs,
+    },
+
+    /// create a Kibana data view and a saved search for the index
+    CreateDataView {
+        #[clap(flatten)]
+        kibana: KibanaArgs,
+    },
 
     // import timeline data
     Import {
         #[clap(default_value="-", help=INPUTFILE_HELP)]
         input_file: String,
 
+        #[clap(flatten)]
+        parser: ParserArgs,
+
         /// number of timeline entries to combine in one bulk operation
         #[clap(long("bulk-size"), default_value_t=1000)]
-        bulk_size: usize
+        bulk_size: usize,
+
+        /// name of the ingest pipeline to send all documents through
+        #[clap(long("pipeline"))]
+        pipeline: Option<String>,
+
+        /// how the ids of the documents are generated
+        #[clap(long("id-strategy"), value_enum, default_value_t=IdStrategyName::ContentHash)]
+        id_strategy: IdStrategyName,
+
+        /// fields used by the 'field-hash' and 'from-field' id strategies
+        #[clap(long("id-fields"), value_delimiter(','))]
+        id_fields: Vec<String>,
+
+        /// bulk operation used to write documents
+        #[clap(long("bulk-mode"), value_enum, default_value_t=BulkMode::Create)]
+        bulk_mode: BulkMode,
+
+        /// write documents which conflict with the mapping of the index into this file
+        /// [default: <INDEX_NAME>-dead-letter.jsonl]
+        #[clap(long("dead-letter"), value_name("FILE"))]
+        dead_letter_file: Option<String>,
+
+        /// add the input file, its hash and the position of the record to every document
+        #[clap(long("provenance"))]
+        provenance: bool,
+
+        /// parse the input and check it against the mapping of the index, but do not import anything
+        #[clap(long("dry-run"))]
+        dry_run: bool,
+    },
+
+    /// convert timeline data into ECS documents, without importing them
+    Convert {
+        /// input files, which may be prefixed by their format (like 'l2tcsv:timeline.csv'),
+        /// or '-' for stdin. Multiple inputs are merged into one timeline, sorted by '@timestamp'
+        #[clap(default_value="-")]
+        input_files: Vec<String>,
+
+        /// sort the documents by '@timestamp', even if there is only one input file
+        #[clap(long("sort"))]
+        sort: bool,
+
+        /// maximum number of documents which are sorted in memory; more documents are
+        /// sorted using temporary files
+        #[clap(long("sort-buffer"), value_name("DOCUMENTS"), default_value_t=100_000)]
+        sort_buffer: usize,
+
+        #[clap(flatten)]
+        parser: ParserArgs,
+
+        /// output format
+        #[clap(long("output-format"), value_enum, default_value_t=ExportFormat::Json)]
+        output_format: ExportFormat,
+
+        /// path of the output file or '-' for stdout
+        #[clap(short('o'), long("output"), default_value="-")]
+        output_file: String,
+    },
+
+    /// list all indices matching the index name, which may contain wildcards
+    ListIndices,
+
+    /// display the mapping and a sample document of the index
+    Inspect,
+
+    /// delete the index
+    DeleteIndex {
+        /// do not ask for confirmation
+        #[clap(long("yes"))]
+        yes: bool,
+    },
+
+    /// add the index to a case alias
+    AttachAlias {
+        /// name of the alias
+        alias: String,
+
+        /// make the index the write index of the alias, so that imports through the alias are written into it
+        #[clap(long("write-index"))]
+        write_index: bool,
+    },
+
+    /// remove the index from a case alias
+    DetachAlias {
+        /// name of the alias
+        alias: String,
+    },
+
+    /// attach an index lifecycle management policy to the index
+    SetLifecycle {
+        /// name of the lifecycle policy
+        policy: String,
+    },
+
+    /// create a snapshot of the index or of all indices of an alias
+    Snapshot {
+        /// name of the registered snapshot repository
+        #[clap(long("repository"))]
+        repository: String,
+
+        /// name of the snapshot [default: <INDEX_NAME>-<CURRENT TIME>]
+        #[clap(long("snapshot"))]
+        snapshot: Option<String>,
+    },
+
+    /// restore the index (or all indices matching the index name) from a snapshot
+    RestoreSnapshot {
+        /// name of the registered snapshot repository
+        #[clap(long("repository"))]
+        repository: String,
+
+        /// name of the snapshot
+        #[clap(long("snapshot"))]
+        snapshot: String,
+
+        /// restore the index under this name; '$1' will be replaced by the original name
+        #[clap(long("rename-to"))]
+        rename_to: Option<String>,
+    },
+
+    /// export the documents of the index, sorted by `@timestamp`
+    Export {
+        /// output format
+        #[clap(short('F'), long("format"), value_enum, default_value_t=ExportFormat::Json)]
+        format: ExportFormat,
+
+        /// path of the output file or '-' for stdout
+        #[clap(short('o'), long("output"), default_value="-")]
+        output_file: String,
+
+        /// export only documents which are not older than this (RFC 3339 format)
+        #[clap(long("from"), value_parser=parse_rfc3339)]
+        from: Option<DateTime<FixedOffset>>,
+
+        /// export only documents which are not newer than this (RFC 3339 format)
+        #[clap(long("to"), value_parser=parse_rfc3339)]
+        to: Option<DateTime<FixedOffset>>,
+
+        /// export only documents which match this query (Lucene syntax)
+        #[clap(long("query"))]
+        query: Option<String>,
+    },
+
+    /// search for documents and display them as timeline
+    Search {
+        /// display only documents which are not older than this (RFC 3339 format)
+        #[clap(long("from"), value_parser=parse_rfc3339, conflicts_with("around"))]
+        from: Option<DateTime<FixedOffset>>,
+
+        /// display only documents which are not newer than this (RFC 3339 format)
+        #[clap(long("to"), value_parser=parse_rfc3339, conflicts_with("around"))]
+        to: Option<DateTime<FixedOffset>>,
+
+        /// display only documents around this pivot timestamp (RFC 3339 format)
+        #[clap(long("around"), value_parser=parse_rfc3339)]
+        around: Option<DateTime<FixedOffset>>,
+
+        /// time window around the pivot timestamp, such as '30s', '5m', '2h' or '1d'
+        #[clap(long("window"), value_parser=parse_duration, default_value="5m", requires("around"))]
+        window: Duration,
+
+        /// display only documents which match this query (Lucene syntax)
+        query: Option<String>,
+
+        /// display only documents of this host (can be used multiple times)
+        #[clap(long("host"))]
+        hosts: Vec<String>,
+
+        /// display only documents with this tag (can be used multiple times)
+        #[clap(long("tag"))]
+        tags: Vec<String>,
+
+        /// maximum number of documents to display
+        #[clap(long("limit"), default_value_t=100)]
+        limit: usize,
+
+        /// output format
+        #[clap(short('F'), long("format"), value_enum, default_value_t=ExportFormat::Table)]
+        format: ExportFormat,
+    },
+
+    /// create or update an ingest pipeline, which sets `event.ingested`
+    PutPipeline {
+        /// name of the ingest pipeline
+        name: String,
+
+        /// read the pipeline definition from a JSON file
+        #[clap(long("from-file"))]
+        definition_file: Option<String>,
+
+        /// case identifier, which will be stored in `forensics.case`
+        #[clap(long("case"))]
+        case: Option<String>,
+
+        /// name of the analyst, which will be stored in `forensics.analyst`
+        #[clap(long("analyst"))]
+        analyst: Option<String>,
+    }
+}
+
+fn parse_rfc3339(value: &str) -> Result<DateTime<FixedOffset>, chrono::ParseError> {
+    DateTime::parse_from_rfc3339(value)
+}
+
+fn parse_timezone(value: &str) -> Result<Tz, String> {
+    value.parse()
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = value.split_at(value.len() - value.chars().last().map_or(0, |c| c.len_utf8()));
+    let number: i64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration: '{value}'"))?;
+    match unit {
+        "s" => Ok(Duration::seconds(number)),
+        "m" => Ok(Duration::minutes(number)),
+        "h" => Ok(Duration::hours(number)),
+        "d" => Ok(Duration::days(number)),
+        _ => Err(format!("invalid unit in duration '{value}', use one of s, m, h or d")),
     }
 }
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
+#[clap(override_usage = "es4forensics [OPTIONS] --index <INDEX_NAME> --password <PASSWORD> <COMMAND>\n       es4forensics [OPTIONS] convert [CONVERT_OPTIONS] [INPUT_FILES]...")]
 pub struct Cli {
     #[command(subcommand)]
     pub(crate) action: Action,
@@ -32,9 +381,9 @@ pub struct Cli {
     #[clap(long("strict"), display_order(500))]
     pub(crate) strict_mode: bool,
 
-    /// name of the elasticsearch index
+    /// name of the elasticsearch index (or a pattern for 'list-indices'), required by all subcommands except 'convert'
     #[clap(short('I'), long("index"), display_order = 800)]
-    pub(crate) index_name: String,
+    pub(crate) index_name: Option<String>,
 
     /// server name or IP address of elasticsearch server
     #[clap(
@@ -66,10 +415,67 @@ pub struct Cli {
     #[clap(short('U'), long("username"), display_order=850, default_value=Some("elastic"))]
     pub(crate) username: String,
 
-    /// password for authenticating at elasticsearch
+    /// password for authenticating at elasticsearch, required by all subcommands except 'convert'
     #[clap(short('W'), long("password"), display_order = 860)]
-    pub(crate) password: String,
+    pub(crate) password: Option<String>,
+
+    /// URL of a HTTP proxy to connect through
+    #[clap(long("proxy"), value_name("URL"), display_order = 870)]
+    pub(crate) proxy: Option<Url>,
+
+    /// username for authenticating at the proxy
+    #[clap(long("proxy-user"), value_name("USERNAME"), display_order = 871, requires("proxy"))]
+    pub(crate) proxy_username: Option<String>,
+
+    /// password for authenticating at the proxy
+    #[clap(long("proxy-password"), value_name("PASSWORD"), display_order = 872, requires("proxy_username"))]
+    pub(crate) proxy_password: Option<String>,
+
+    /// global timeout (in seconds) for requests to elasticsearch
+    #[clap(long("timeout"), value_name("SECONDS"), display_order = 880)]
+    pub(crate) timeout: Option<u64>,
+
+    /// timeout (in seconds) for a single API request
+    #[clap(long("request-timeout"), value_name("SECONDS"), display_order = 881)]
+    pub(crate) request_timeout: Option<u64>,
 
     #[clap(flatten)]
     pub(crate) verbose: clap_verbosity_flag::Verbosity,
-}
\ No newline at end of file
+}
+
+impl Cli {
+    /// parses the command line. `--index` and `--password` cannot be declared as required,
+    /// because `convert` does not connect to elasticsearch, so they are checked here and
+    /// reported like any other missing argument.
+    pub(crate) fn parse_args() -> Self {
+        let cli = Self::parse();
+        if !matches!(cli.action, Action::Convert { .. }) {
+            let missing: Vec<&str> = [
+                (cli.index_name.is_none(), "--index <INDEX_NAME>"),
+                (cli.password.is_none(), "--password <PASSWORD>"),
+            ]
+            .into_iter()
+            .filter_map(|(is_missing, arg)| is_missing.then_some(arg))
+            .collect();
+            if !missing.is_empty() {
+                Self::command()
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        format!("the following required arguments were not provided:\n  {}", missing.join("\n  ")),
+                    )
+                    .exit();
+            }
+        }
+        cli
+    }
+
+    /// name of the index, which is always present for subcommands other than `convert`
+    pub(crate) fn index_name(&self) -> &str {
+        self.index_name.as_deref().expect("--index is checked by Cli::parse_args")
+    }
+
+    /// password for elasticsearch, which is always present for subcommands other than `convert`
+    pub(crate) fn password(&self) -> &str {
+        self.password.as_deref().expect("--password is checked by Cli::parse_args")
+    }
+}
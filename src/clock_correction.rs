@@ -0,0 +1,194 @@
+use std::{collections::HashMap, fmt::Display, fs::File, io::BufReader, path::Path, str::FromStr};
+
+use anyhow::{anyhow, bail, Result};
+use chrono::Duration;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    timestamp::DATE_FIELDS,
+    utils::json::{get_field, remove_field, set_field},
+    Timestamp,
+};
+
+/// An offset which is added to timestamps, written as `[+-]HH:MM:SS[.fff]`
+///
+/// ```
+/// use es4forensics::ClockOffset;
+///
+/// let offset: ClockOffset = "-00:03:12.5".parse().unwrap();
+/// assert_eq!(offset.duration().num_milliseconds(), -192_500);
+/// assert_eq!(offset.to_string(), "-00:03:12.500");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockOffset(Duration);
+
+impl ClockOffset {
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+}
+
+impl FromStr for ClockOffset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow!("invalid clock offset '{s}', expected [+-]HH:MM:SS[.fff]");
+        let (sign, value) = match s.strip_prefix('-') {
+            Some(value) => (-1, value),
+            None => (1, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let parts: Vec<&str> = value.split(':').collect();
+        let [hours, minutes, seconds] = parts[..] else {
+            return Err(invalid());
+        };
+        let hours: i64 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i64 = minutes.parse().map_err(|_| invalid())?;
+        let seconds: f64 = seconds.parse().map_err(|_| invalid())?;
+        if minutes >= 60 || !(0.0..60.0).contains(&seconds) {
+            return Err(invalid());
+        }
+        let nanos = ((hours * 3600 + minutes * 60) as f64 + seconds) * 1e9;
+        Ok(Self(Duration::nanoseconds(sign * nanos.round() as i64)))
+    }
+}
+
+impl Display for ClockOffset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < Duration::zero() { '-' } else { '+' };
+        let total = self.0.abs();
+        let secs = total.num_seconds();
+        write!(f, "{sign}{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)?;
+        match total.subsec_nanos() {
+            0 => Ok(()),
+            nanos if nanos % 1_000_000 == 0 => write!(f, ".{:03}", nanos / 1_000_000),
+            nanos => write!(f, ".{nanos:09}"),
+        }
+    }
+}
+
+/// Corrects the timestamps of documents which come from hosts with a wrong clock.
+///
+/// The offset is added to `@timestamp` and to all other date fields which have
+/// been recorded by the evidence host (like `file.mtime`). The original values
+/// and the offset are stored in `forensics.clock_correction`.
+#[derive(Clone, Default)]
+pub struct ClockCorrection {
+    default_offset: Option<ClockOffset>,
+    host_offsets: HashMap<String, ClockOffset>,
+}
+
+impl ClockCorrection {
+    /// sets the offset of all documents without a host specific offset
+    pub fn with_offset(mut self, offset: ClockOffset) -> Self {
+        self.default_offset = Some(offset);
+        self
+    }
+
+    /// sets the offset of all documents whose `host.name` (or `host.hostname`) is `host`
+    pub fn with_host_offset(mut self, host: &str, offset: ClockOffset) -> Self {
+        self.host_offsets.insert(host.to_lowercase(), offset);
+        self
+    }
+
+    /// reads host specific offsets from a JSON file, which looks like `{"<host>": "+00:03:12"}`
+    pub fn with_host_offsets_from<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let definition: Value = serde_json::from_reader(reader)?;
+        let hosts = definition
+            .as_object()
+            .ok_or_else(|| anyhow!("the clock offsets must be a JSON object"))?;
+        for (host, offset) in hosts {
+            match offset.as_str() {
+                Some(offset) => self = self.with_host_offset(host, offset.parse()?),
+                None => bail!("invalid clock offset of host '{host}': {offset}"),
+            }
+        }
+        Ok(self)
+    }
+
+    /// returns the offset which applies to `document`, if any
+    pub fn offset_of(&self, document: &Value) -> Option<ClockOffset> {
+        self.offset_of_fields(|field| get_field(document, field))
+    }
+
+    /// returns the offset which applies to a document whose fields are read by `field`
+    pub(crate) fn offset_of_fields<'a>(&self, field: impl Fn(&str) -> Option<&'a Value>) -> Option<ClockOffset> {
+        ["host.name", "host.hostname"]
+            .iter()
+            .filter_map(|name| field(name).and_then(|h| h.as_str()))
+            .find_map(|host| self.host_offsets.get(&host.to_lowercase()))
+            .or(self.default_offset.as_ref())
+            .copied()
+    }
+
+    /// corrects all timestamps of `document`. Timestamps which cannot be read are kept unchanged.
+    pub fn apply(&self, document: &mut Value) {
+        let offset = match self.offset_of(document) {
+            Some(offset) if offset.duration() != Duration::zero() => offset,
+            _ => return,
+        };
+
+        let mut original = Map::new();
+        // all date fields contain the time of the evidence host's clock
+        for field in DATE_FIELDS {
+            let value = match get_field(document, field) {
+                Some(value) if !value.is_null() => value.clone(),
+                _ => continue,
+            };
+            match Self::corrected(&value, offset) {
+                Some(corrected) => {
+                    set_field(document, field, corrected);
+                    original.insert(field.to_owned(), value);
+                }
+                None => log::warn!("unable to correct the clock offset of '{field}': {value}"),
+            }
+        }
+
+        let mut correction = json!({"offset": offset.to_string()});
+        for (field, value) in original {
+            set_field(&mut correction, &format!("original.{field}"), value);
+        }
+        if !set_field(document, "forensics.clock_correction", correction) {
+            log::warn!("unable to set 'forensics.clock_correction', because a parent field is not an object");
+        }
+    }
+
+    /// returns `ts` corrected by `offset`, or unchanged if the result would be out of range
+    pub(crate) fn corrected_timestamp(ts: &Timestamp, offset: ClockOffset) -> Timestamp {
+        match ts.as_datetime().checked_add_signed(offset.duration()) {
+            Some(corrected) => Timestamp::from(corrected),
+            None => ts.clone(),
+        }
+    }
+
+    /// restores the original timestamps of a document which has been corrected by
+    /// [`ClockCorrection::apply`] and removes `forensics.clock_correction`
+    pub(crate) fn revert(document: &mut Value) {
+        let original = match remove_field(document, "forensics.clock_correction.original") {
+            Some(original) => original,
+            None => return,
+        };
+        remove_field(document, "forensics.clock_correction");
+        for field in DATE_FIELDS {
+            if let Some(value) = get_field(&original, field) {
+                set_field(document, field, value.clone());
+            }
+        }
+    }
+
+    /// adds `offset` to a timestamp, which keeps its representation (milliseconds or ISO-8601)
+    fn corrected(value: &Value, offset: ClockOffset) -> Option<Value> {
+        match value {
+            Value::Number(n) => {
+                let millis = n.as_i64()? + offset.duration().num_milliseconds();
+                Some(json!(millis))
+            }
+            Value::String(s) => {
+                let ts = Timestamp::from_iso8601(s).ok()?;
+                let corrected = ts.as_datetime().checked_add_signed(offset.duration())?;
+                Some(json!(Timestamp::from(corrected).to_iso8601()))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+/// File which receives rejected documents. It is only created when the first
+/// document is rejected, so that successful imports do not leave empty files behind.
+pub(crate) struct DeadLetterFile {
+    path: String,
+    file: Option<BufWriter<File>>,
+}
+
+impl DeadLetterFile {
+    pub fn new(path: String) -> Self {
+        Self { path, file: None }
+    }
+}
+
+impl Write for DeadLetterFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.file.is_none() {
+            log::warn!("writing rejected documents into '{}'", self.path);
+            self.file = Some(BufWriter::new(File::create(&self.path)?));
+        }
+        self.file.as_mut().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
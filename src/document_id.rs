@@ -0,0 +1,78 @@
+use anyhow::{anyhow, Result};
+use base64::{encode_config, URL_SAFE_NO_PAD};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    utils::{
+        canonical_json::{canonical_date, canonical_document, canonical_string},
+        json::get_field,
+    },
+    timestamp::DATE_FIELDS,
+    ClockCorrection, Provenance,
+};
+
+/// Specifies how the `_id` of a document is generated
+#[derive(Clone, Default)]
+pub enum IdStrategy {
+    /// SHA-256 hash over the canonicalized JSON document. Provenance fields
+    /// (see [`Provenance`]) are not hashed, because they describe where the
+    /// evidence has been read from and not the event itself. Timestamps which
+    /// have been corrected by a [`ClockCorrection`] are hashed with their original
+    /// values, so that correcting the clock of an import replaces its documents.
+    /// Dates are hashed in a canonical form, so that the id does not depend on the
+    /// [`crate::TimestampFormat`].
+    #[default]
+    ContentHash,
+
+    /// SHA-256 hash over the values of the listed fields only. Use this if
+    /// documents should be replaced after their other fields have been corrected.
+    /// Like [`IdStrategy::ContentHash`], it does not depend on the format of dates.
+    FieldHash(Vec<String>),
+
+    /// let elasticsearch generate a random id
+    Auto,
+
+    /// use the value of the named field as id
+    FromField(String),
+}
+
+impl IdStrategy {
+    /// returns the id of `document`, or `None` if elasticsearch shall generate the id
+    pub fn document_id(&self, document: &Value) -> Result<Option<String>> {
+        match self {
+            IdStrategy::ContentHash => Ok(Some(hash(&canonical_document(&hashed_content(document))))),
+            IdStrategy::FieldHash(fields) => {
+                let values: Vec<Value> = fields
+                    .iter()
+                    .map(|f| match get_field(document, f) {
+                        Some(value) if DATE_FIELDS.contains(&f.as_str()) => canonical_date(value),
+                        Some(value) => value.clone(),
+                        None => Value::Null,
+                    })
+                    .collect();
+                Ok(Some(hash(&canonical_string(&json!(values)))))
+            }
+            IdStrategy::Auto => Ok(None),
+            IdStrategy::FromField(field) => match get_field(document, field) {
+                Some(Value::String(s)) => Ok(Some(s.clone())),
+                Some(Value::Number(n)) => Ok(Some(n.to_string())),
+                _ => Err(anyhow!("document has no usable id in field '{field}'")),
+            },
+        }
+    }
+}
+
+/// returns the part of `document` which is hashed by [`IdStrategy::ContentHash`]
+fn hashed_content(document: &Value) -> Value {
+    let mut content = document.clone();
+    Provenance::remove(&mut content);
+    ClockCorrection::revert(&mut content);
+    content
+}
+
+fn hash(data: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    encode_config(hasher.finalize(), URL_SAFE_NO_PAD)
+}
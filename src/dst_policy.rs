@@ -0,0 +1,71 @@
+/// how to resolve a local time which occurs twice, because the clock has been
+/// set back at the end of daylight saving time
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum AmbiguousTime {
+    /// use the first occurrence, i.e. the one in daylight saving time
+    #[default]
+    Earliest,
+
+    /// use the second occurrence, i.e. the one in standard time
+    Latest,
+
+    /// reject the timestamp
+    Error,
+}
+
+/// how to resolve a local time which does not exist, because the clock has
+/// been set forward at the beginning of daylight saving time
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum NonexistentTime {
+    /// reject the timestamp
+    #[default]
+    Error,
+
+    /// interpret the time with the offset before the transition, which moves
+    /// it forward by the length of the gap (e.g. 02:30 becomes 03:30)
+    ShiftForward,
+}
+
+/// Policy for local timestamps which are affected by a daylight saving time transition.
+///
+/// Timestamps which have been resolved by this policy are marked (see
+/// [`DstAdjustment`]), so that the documents containing them can be tagged.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct DstPolicy {
+    pub ambiguous: AmbiguousTime,
+    pub nonexistent: NonexistentTime,
+}
+
+impl DstPolicy {
+    pub fn with_ambiguous(mut self, ambiguous: AmbiguousTime) -> Self {
+        self.ambiguous = ambiguous;
+        self
+    }
+
+    pub fn with_nonexistent(mut self, nonexistent: NonexistentTime) -> Self {
+        self.nonexistent = nonexistent;
+        self
+    }
+}
+
+/// describes how a local timestamp has been resolved by a [`DstPolicy`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DstAdjustment {
+    /// the local time was ambiguous, and one of its occurrences has been chosen
+    Ambiguous,
+
+    /// the local time did not exist, and has been shifted forward
+    Shifted,
+}
+
+impl DstAdjustment {
+    /// tag which is added to documents containing such a timestamp
+    pub fn tag(&self) -> &'static str {
+        match self {
+            DstAdjustment::Ambiguous => "timestamp_ambiguous",
+            DstAdjustment::Shifted => "timestamp_shifted",
+        }
+    }
+}
@@ -1,34 +1,69 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::BTreeMap;
 
 use anyhow::bail;
 use duplicate::duplicate_item;
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
 
 use crate::ecs::ecs_object::EcsObject;
-use crate::ecs::{Event, Host, log::Log, File};
+use crate::ecs::{Event, Host, log::Log, File, User};
 use crate::timestamp::Timestamp;
+use crate::utils::json::{get_field, set_field};
 
 pub struct EcsBuilder {
     ts: Timestamp,
     message: String,
     //labels: HashMap<String, String>,
-    tags: HashSet<String>,
-    contents: HashMap<&'static str, Value>
+
+    /// tags in the order in which they were added
+    tags: Vec<String>,
+
+    /// tags of DST adjustments, which follow all other tags
+    timestamp_tags: Vec<String>,
+
+    /// ECS field sets, sorted by their name
+    contents: BTreeMap<String, Value>
 }
 
 impl EcsBuilder {
 
     pub fn new(message: String, ts: Timestamp) -> Self {
+        let timestamp_tags = ts.dst_adjustment().map(|a| a.tag().to_owned()).into_iter().collect();
         Self {
             ts,
             message,
-            tags: HashSet::default(),
-            contents: HashMap::default()
+            tags: Vec::default(),
+            timestamp_tags,
+            contents: BTreeMap::default()
         }
     }
 
+    /// the timestamp of the document, which becomes its `@timestamp`
+    pub fn timestamp(&self) -> &Timestamp {
+        &self.ts
+    }
+
+    /// returns a (possibly nested) field of a field set which has been added,
+    /// such as `file.path`
+    pub fn field(&self, path: &str) -> Option<&Value> {
+        let (field_set, field) = path.split_once('.')?;
+        get_field(self.contents.get(field_set)?, field)
+    }
+
+    /// marks the document if `ts`, which is contained in the document, has been
+    /// adjusted because of a DST transition
+    pub fn with_timestamp_tags(mut self, ts: &Timestamp) -> Self {
+        if let Some(tag) = ts.dst_adjustment().map(|a| a.tag()) {
+            if ! self.timestamp_tags.iter().any(|t| t == tag) {
+                self.timestamp_tags.push(tag.to_owned());
+            }
+        }
+        self
+    }
+
     pub fn with_additional_tag(mut self, tag: &str) -> Self {
-        self.tags.insert(tag.to_owned());
+        if ! self.tags.iter().any(|t| t == tag) {
+            self.tags.push(tag.to_owned());
+        }
         self
     }
 
@@ -38,35 +73,79 @@ impl EcsBuilder {
     [ with_host ]  [ Host ];
     [ with_log ]   [ Log ];
     [ with_file ]  [ File ];
+    [ with_user ]  [ User ];
     )]
     pub fn method(mut self, ts: ret_type) -> anyhow::Result<Self> {
         if self.contents.contains_key(ts.object_key()) {
             bail!("unambigious key: '{}'", ts.object_key());
         }
-        self.contents.insert(ts.object_key(), json!(ts));
+        self.contents.insert(ts.object_key().to_owned(), json!(ts));
+        Ok(self)
+    }
+
+    /// adds fields which are not part of ECS, such as the original attributes
+    /// of a record, below the custom field set `namespace`
+    pub fn with_custom_fields(mut self, namespace: &str, fields: Value) -> anyhow::Result<Self> {
+        if self.contents.contains_key(namespace) {
+            bail!("unambigious key: '{}'", namespace);
+        }
+        self.contents.insert(namespace.to_owned(), fields);
+        Ok(self)
+    }
+
+    /// sets a single (possibly nested) field, such as `url.full`. The field is
+    /// merged into field sets which have already been added.
+    pub fn with_field(mut self, path: &str, value: Value) -> anyhow::Result<Self> {
+        let (field_set, field) = match path.split_once('.') {
+            Some((field_set, field)) => (field_set, Some(field)),
+            None => (path, None),
+        };
+        match field {
+            None => {
+                self.contents.insert(field_set.to_owned(), value);
+            }
+            Some(field) => {
+                let fields = self
+                    .contents
+                    .entry(field_set.to_owned())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if !set_field(fields, field, value) {
+                    bail!("unable to set '{path}', because a parent field is not an object");
+                }
+            }
+        }
         Ok(self)
     }
 }
 
+/// The generated document always has the same order of keys: first the
+/// ECS base fields, then all field sets in alphabetical order. Tags of DST
+/// adjustments follow all other tags.
 impl From<EcsBuilder> for (Timestamp, Value) {
     fn from(val: EcsBuilder) -> (Timestamp, Value) {
-        let mut m = HashMap::from([
-            (
-                "@timestamp",
-                Value::Number(val.ts.timestamp_millis().into()),
-            ),
-            ("ecs", json!({"version": "8.4"})),
-            ("message", json!(val.message))
-        ]);
-
-        if !val.tags.is_empty() {
-            m.insert("tags", json!(val.tags));
+        let mut m = Map::new();
+        m.insert(
+            "@timestamp".to_owned(),
+            Value::from(&val.ts),
+        );
+        m.insert("message".to_owned(), json!(val.message));
+
+        let mut tags = val.tags;
+        for tag in val.timestamp_tags {
+            if ! tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if !tags.is_empty() {
+            m.insert("tags".to_owned(), json!(tags));
         }
 
-        for (key, value) in val.contents.into_iter() {
+        let mut field_sets = val.contents;
+        field_sets.insert("ecs".to_owned(), json!({"version": "8.4"}));
+        for (key, value) in field_sets.into_iter() {
             m.insert(key, value);
         }
-        (val.ts, json!(m))
+        (val.ts, Value::Object(m))
 
     }
 }
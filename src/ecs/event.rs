@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use duplicate::duplicate_item;
 use serde::Serialize;
@@ -6,10 +6,11 @@ use serde_json::Value;
 
 use super::{CustomizableField, ecs_object::EcsObject};
 
-#[derive(Serialize)]
+#[derive(Serialize, Default)]
 pub enum Kind {
     Alert,
     Enrichment,
+    #[default]
     Event,
     Metric,
     State,
@@ -17,12 +18,6 @@ pub enum Kind {
     Signal,
 }
 
-impl Default for Kind {
-    fn default() -> Self {
-        Kind::Event
-    }
-}
-
 #[derive(Serialize)]
 pub enum Category {
     Authentication,
@@ -80,11 +75,12 @@ pub struct Event<'a> {
     event_outcome: Option<Outcome>,
     code: Option<u64>,
     activity: Option<String>,
+    action: Option<String>,
     sequence: Option<String>,
     module: Option<String>,
     provider: Option<String>,
     severity: Option<u8>,
-    custom_data: HashMap<&'a String, &'a Value>,
+    custom_data: BTreeMap<&'a String, &'a Value>,
 }
 
 impl<'a> Event<'a> {
@@ -99,6 +95,7 @@ impl<'a> Event<'a> {
       [ with_type ]     [ event_type ]     [ Type ];
       [ with_outcome ]  [ event_outcome ]  [ Outcome ];
       [ with_code ]     [ code ]           [ u64 ];
+      [ with_action ]   [ action ]         [ String ];
       [ with_sequence ] [ sequence ]       [ String ];
       [ with_module ]   [ module ]         [ String ];
       [ with_provider ] [ provider ]       [ String ];
@@ -5,7 +5,7 @@ use serde::Serialize;
 
 use crate::timestamp::Timestamp;
 
-use super::ecs_object::EcsObject;
+use super::{ecs_object::EcsObject, objects::Macb};
 
 #[derive(Serialize)]
 pub enum FileType {
@@ -33,17 +33,33 @@ pub struct File {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     extension: Option<String>,
-    gid: u64,
-    uid: u64,
-    inode: String,
-    mode: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gid: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uid: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inode: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    macb_short: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    macb_long: Option<Vec<&'static str>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     name: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     path: Option<String>,
-    size: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     target_path: Option<String>,
@@ -89,7 +105,15 @@ impl File {
       [ with_size ]  [ size ]  [ u64 ];
    )]
     pub fn method(mut self, ts: ret_type) -> Self {
-        self.attribute = ts;
+        self.attribute = Some(ts);
+        self
+    }
+
+    /// sets `macb_short` and `macb_long`, which describe which of the timestamps
+    /// of the file are equal to the timestamp of the document
+    pub fn with_macb(mut self, macb: &Macb) -> Self {
+        self.macb_short = Some(macb.into());
+        self.macb_long = Some(macb.into());
         self
     }
 }
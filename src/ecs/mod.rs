@@ -1,5 +1,6 @@
 mod event;
 mod host;
+mod user;
 pub mod log;
 mod file;
 mod ecs_builder;
@@ -9,6 +10,7 @@ pub mod objects;
 pub use ecs_builder::*;
 pub use event::*;
 pub use host::*;
+pub use user::*;
 pub use file::*;
 pub use timeline_object::TimelineObject;
 
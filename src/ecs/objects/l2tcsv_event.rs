@@ -0,0 +1,171 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::NaiveDateTime;
+use chrono_tz::Tz;
+use csv::StringRecord;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    ecs::{ecs_builder::EcsBuilder, timeline_object::TimelineObject, *},
+    timestamp::Timestamp,
+    DstPolicy,
+};
+
+use super::Macb;
+
+/// columns of the `l2tcsv` output format of `psort.py`
+pub const L2TCSV_HEADER: [&str; 17] = [
+    "date", "time", "timezone", "MACB", "source", "sourcetype", "type", "user", "host", "short",
+    "desc", "version", "filename", "inode", "notes", "format", "extra",
+];
+
+/// An event which has been exported by plaso in `l2tcsv` format
+///
+/// ```
+/// use es4forensics::objects::L2tCsvEvent;
+/// use es4forensics::TimelineObject;
+///
+/// let line = r#"12/23/2019,09:15:11,UTC,M..B,FILE,NTFS MFT,Creation Time,-,dc01,short,desc,2,/Users/Administrator,93552,-,mft,"file_size: 92; is_allocated: True""#;
+/// let event = L2tCsvEvent::try_from(line).unwrap();
+/// let values: Vec<_> = event.into_values().collect();
+/// assert_eq!(values[0]["file"]["macb_short"], "m..b");
+/// ```
+pub struct L2tCsvEvent {
+    timestamp: Timestamp,
+    macb: Macb,
+    record: StringRecord,
+}
+
+impl L2tCsvEvent {
+    fn column(&self, idx: usize) -> Option<&str> {
+        match self.record.get(idx) {
+            None | Some("") | Some("-") => None,
+            Some(value) => Some(value),
+        }
+    }
+
+    /// parses the `extra` column, which contains `key: value` pairs separated by `;`
+    fn extra(&self) -> Value {
+        let mut extra = Map::new();
+        for pair in self.column(16).unwrap_or_default().split(';') {
+            if let Some((key, value)) = pair.split_once(':') {
+                extra.insert(key.trim().to_owned(), json!(value.trim()));
+            }
+        }
+        Value::Object(extra)
+    }
+
+    fn into_builder(self) -> Result<EcsBuilder> {
+        let message = self.column(10).or_else(|| self.column(9)).unwrap_or_default();
+        let mut event = Event::default().with_kind(Kind::Event);
+        if let Some(parser) = self.column(15) {
+            event = event.with_module(parser.to_owned());
+        }
+        if let Some(sourcetype) = self.column(5) {
+            event = event.with_provider(sourcetype.to_owned());
+        }
+        if let Some(timestamp_desc) = self.column(6) {
+            event = event.with_action(timestamp_desc.to_owned());
+        }
+
+        let plaso = json!({
+            "source": self.column(4),
+            "source_long": self.column(5),
+            "timestamp_desc": self.column(6),
+            "short": self.column(9),
+            "version": self.column(11),
+            "notes": self.column(14),
+            "parser": self.column(15),
+            "extra": self.extra(),
+        });
+
+        let mut builder = EcsBuilder::new(message.to_owned(), self.timestamp.clone())
+            .with_additional_tag("l2tcsv")
+            .with_event(event)?
+            .with_custom_fields("plaso", plaso)?;
+
+        if let Some(host) = self.column(8) {
+            builder = builder.with_host(Host::from(&json!(host)))?;
+        }
+        if let Some(user) = self.column(7) {
+            builder = builder.with_user(User::from(user.to_owned()))?;
+        }
+        if let Some(filename) = self.column(12) {
+            let mut file = File::from(filename.to_owned()).with_macb(&self.macb);
+            if let Some(inode) = self.column(13) {
+                file = file.with_inode(inode.to_owned());
+            }
+            builder = builder.with_file(file)?;
+        }
+        Ok(builder)
+    }
+}
+
+impl TimelineObject for L2tCsvEvent {}
+
+impl IntoIterator for L2tCsvEvent {
+    type Item = anyhow::Result<EcsBuilder>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+    fn into_iter(self) -> Self::IntoIter {
+        vec![self.into_builder()].into_iter()
+    }
+}
+
+impl TryFrom<StringRecord> for L2tCsvEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(record: StringRecord) -> Result<Self> {
+        Self::try_from((record, DstPolicy::default()))
+    }
+}
+
+impl TryFrom<(StringRecord, DstPolicy)> for L2tCsvEvent {
+    type Error = anyhow::Error;
+
+    fn try_from((record, policy): (StringRecord, DstPolicy)) -> Result<Self> {
+        if record.len() != L2TCSV_HEADER.len() {
+            bail!(
+                "expected {} columns in l2tcsv record, but found {}",
+                L2TCSV_HEADER.len(),
+                record.len()
+            );
+        }
+
+        // the date and time are given in the timezone of the record
+        let local_time = NaiveDateTime::parse_from_str(
+            &format!("{} {}", &record[0], &record[1]),
+            "%m/%d/%Y %H:%M:%S",
+        )?;
+        let tz: Tz = record[2]
+            .parse()
+            .map_err(|why| anyhow!("invalid timezone '{}': {why}", &record[2]))?;
+        let timestamp = Timestamp::from_local(&local_time, &tz, policy)?;
+
+        Ok(Self {
+            timestamp,
+            macb: Macb::from(&record[3]),
+            record,
+        })
+    }
+}
+
+impl TryFrom<&str> for L2tCsvEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(line: &str) -> Result<Self> {
+        Self::try_from((line, DstPolicy::default()))
+    }
+}
+
+impl TryFrom<(&str, DstPolicy)> for L2tCsvEvent {
+    type Error = anyhow::Error;
+
+    fn try_from((line, policy): (&str, DstPolicy)) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(line.as_bytes());
+        match reader.records().next() {
+            Some(record) => Self::try_from((record?, policy)),
+            None => bail!("empty l2tcsv record"),
+        }
+    }
+}
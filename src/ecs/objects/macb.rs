@@ -1,5 +1,5 @@
 
-#[derive(Default)]
+#[derive(Default, Debug, PartialEq)]
 pub struct Macb {
     pub modified: bool,
     pub accessed: bool,
@@ -19,7 +19,7 @@ impl From<&Macb> for String {
     }
 }
 
-impl From<&Macb> for Vec<&str> {
+impl From<&Macb> for Vec<&'static str> {
     fn from(me: &Macb) -> Self {
         let mut res = Vec::new();
         if me.modified { res.push("modified"); }
@@ -28,4 +28,21 @@ impl From<&Macb> for Vec<&str> {
         if me.created { res.push("created"); }
         res
     }
-}
\ No newline at end of file
+}
+
+/// parses MACB strings like `m.c.` (as written by mactime) or `M..B` (as written by plaso)
+impl From<&str> for Macb {
+    fn from(macb: &str) -> Self {
+        let flag = |idx: usize, c: char| {
+            macb.chars()
+                .nth(idx)
+                .is_some_and(|f| f.eq_ignore_ascii_case(&c))
+        };
+        Self {
+            modified: flag(0, 'm'),
+            accessed: flag(1, 'a'),
+            changed: flag(2, 'c'),
+            created: flag(3, 'b'),
+        }
+    }
+}
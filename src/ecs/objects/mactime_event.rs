@@ -0,0 +1,129 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::NaiveDateTime;
+use chrono_tz::Tz;
+
+use crate::{
+    ecs::{ecs_builder::EcsBuilder, timeline_object::TimelineObject, File},
+    timestamp::Timestamp,
+    DstPolicy,
+};
+
+use super::Macb;
+
+/// columns of the delimited output of `mactime -d`
+pub const MACTIME_HEADER: [&str; 8] = ["Date", "Size", "Type", "Mode", "UID", "GID", "Meta", "File Name"];
+
+/// date formats written by `mactime`, by default and with `-y`
+const MACTIME_DATE_FORMATS: [&str; 2] = ["%a %b %d %Y %H:%M:%S", "%Y-%m-%dT%H:%M:%S"];
+
+/// A single timestamp of a file, as written by `mactime -d`
+///
+/// `mactime` writes the date in the timezone given by its `-z` option, so this
+/// timezone must be passed to interpret the date correctly.
+///
+/// ```
+/// use es4forensics::objects::MactimeEvent;
+/// use es4forensics::TimelineObject;
+///
+/// let line = "Mon Dec 23 2019 09:15:11,92,m.c.,r/rrwxr-xr-x,0,0,4711-128-1,/etc/passwd";
+/// let event = MactimeEvent::try_from((line, &chrono_tz::UTC)).unwrap();
+/// let values: Vec<_> = event.into_values().collect();
+/// assert_eq!(values[0]["file"]["macb_short"], "m.c.");
+/// ```
+pub struct MactimeEvent {
+    timestamp: Timestamp,
+    size: u64,
+    macb: Macb,
+    mode: String,
+    uid: u64,
+    gid: u64,
+    meta: String,
+    name: String,
+}
+
+impl MactimeEvent {
+    /// the time of the row, converted from the timezone in which mactime has written it
+    pub fn timestamp(&self) -> &Timestamp {
+        &self.timestamp
+    }
+
+    fn parse_date(date: &str, tz: &Tz, policy: DstPolicy) -> Result<Timestamp> {
+        let date = date.trim().trim_end_matches('Z');
+        let local_time = MACTIME_DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+            .ok_or_else(|| anyhow!("invalid mactime date: '{date}'"))?;
+        Timestamp::from_local(&local_time, tz, policy)
+    }
+
+    fn into_builder(self) -> Result<EcsBuilder> {
+        // the timestamp of the row is the value of all timestamps named in the MACB string
+        let ts = |flag: bool| flag.then(|| self.timestamp.clone());
+        let file = File::from(self.name.clone())
+            .with_inode(self.meta)
+            .with_mode(self.mode)
+            .with_uid(self.uid)
+            .with_gid(self.gid)
+            .with_size(self.size)
+            .with_mtime(ts(self.macb.modified))
+            .with_accessed(ts(self.macb.accessed))
+            .with_ctime(ts(self.macb.changed))
+            .with_created(ts(self.macb.created))
+            .with_macb(&self.macb);
+        EcsBuilder::new(self.name, self.timestamp)
+            .with_additional_tag("mactime")
+            .with_file(file)
+    }
+}
+
+impl TimelineObject for MactimeEvent {}
+
+impl IntoIterator for MactimeEvent {
+    type Item = anyhow::Result<EcsBuilder>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+    fn into_iter(self) -> Self::IntoIter {
+        vec![self.into_builder()].into_iter()
+    }
+}
+
+impl TryFrom<(&str, &Tz)> for MactimeEvent {
+    type Error = anyhow::Error;
+
+    fn try_from((line, tz): (&str, &Tz)) -> Result<Self> {
+        Self::try_from((line, tz, DstPolicy::default()))
+    }
+}
+
+impl TryFrom<(&str, &Tz, DstPolicy)> for MactimeEvent {
+    type Error = anyhow::Error;
+
+    fn try_from((line, tz, policy): (&str, &Tz, DstPolicy)) -> Result<Self> {
+        // mactime does not quote file names, so the last column takes all remaining commas
+        let columns: Vec<&str> = line.splitn(MACTIME_HEADER.len(), ',').collect();
+        if columns.len() != MACTIME_HEADER.len() {
+            bail!(
+                "expected {} columns in mactime record, but found {}",
+                MACTIME_HEADER.len(),
+                columns.len()
+            );
+        }
+        Ok(Self {
+            timestamp: Self::parse_date(columns[0], tz, policy)?,
+            size: columns[1].parse()?,
+            macb: Macb::from(columns[2]),
+            mode: columns[3].to_owned(),
+            uid: columns[4].parse()?,
+            gid: columns[5].parse()?,
+            meta: columns[6].to_owned(),
+            name: columns[7].to_owned(),
+        })
+    }
+}
+
+impl TryFrom<&str> for MactimeEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(line: &str) -> Result<Self> {
+        Self::try_from((line, &Tz::UTC))
+    }
+}
@@ -5,6 +5,9 @@ mod posix_file;
 mod ntfs_file;
 mod simple_event;
 mod macb;
+mod l2tcsv_event;
+mod plaso_event;
+mod mactime_event;
 
 pub use ad_object::*;
 pub use registry_key::*;
@@ -13,3 +16,6 @@ pub use posix_file::*;
 pub use ntfs_file::*;
 pub use simple_event::*;
 pub use macb::*;
+pub use l2tcsv_event::*;
+pub use plaso_event::*;
+pub use mactime_event::*;
@@ -0,0 +1,194 @@
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    ecs::{ecs_builder::EcsBuilder, timeline_object::TimelineObject},
+    timestamp::Timestamp,
+};
+
+/// attributes which are stored in ECS fields for all data types
+const COMMON_FIELDS: [(&str, &str); 12] = [
+    ("data_type", "event.dataset"),
+    ("parser", "event.module"),
+    ("timestamp_desc", "event.action"),
+    ("filename", "file.path"),
+    ("file_entry_type", "file.type"),
+    ("file_size", "file.size"),
+    ("inode", "file.inode"),
+    ("md5_hash", "file.hash.md5"),
+    ("sha256_hash", "file.hash.sha256"),
+    ("hostname", "host.name"),
+    ("username", "user.name"),
+    ("user_sid", "user.id"),
+];
+
+/// attributes which are stored in ECS fields only for specific data types
+const DATA_TYPE_FIELDS: [(&str, &str, &str); 12] = [
+    ("windows:evtx:record", "event_identifier", "event.code"),
+    ("windows:evtx:record", "record_number", "event.sequence"),
+    ("windows:evtx:record", "source_name", "event.provider"),
+    ("windows:evtx:record", "computer_name", "host.name"),
+    ("windows:registry:key_value", "key_path", "registry.path"),
+    ("windows:prefetch:execution", "executable", "process.name"),
+    ("windows:prefetch:execution", "path_hints", "process.executable"),
+    ("chrome:history:page_visited", "url", "url.full"),
+    ("firefox:places:page_visited", "url", "url.full"),
+    ("syslog:line", "pid", "process.pid"),
+    ("syslog:line", "reporter", "process.name"),
+    ("syslog:line", "severity", "log.level"),
+];
+
+/// attributes which describe the container or which are already part of every document
+const IGNORED_ATTRIBUTES: [&str; 5] = ["__container_type__", "__type__", "timestamp", "date_time", "message"];
+
+/// Specifies which attributes of plaso events are stored in which ECS fields.
+/// All other attributes are stored below `plaso.*`.
+///
+/// ```
+/// use es4forensics::objects::PlasoMapping;
+///
+/// let mapping = PlasoMapping::default()
+///     .with_field("windows:lnk:link", "drive_serial_number", "file.drive_letter");
+/// assert_eq!(mapping.ecs_field("fs:stat", "filename"), Some("file.path"));
+/// assert_eq!(mapping.ecs_field("windows:lnk:link", "drive_serial_number"), Some("file.drive_letter"));
+/// ```
+#[derive(Clone)]
+pub struct PlasoMapping {
+    common: HashMap<String, String>,
+    data_types: HashMap<String, HashMap<String, String>>,
+}
+
+impl Default for PlasoMapping {
+    fn default() -> Self {
+        let mut me = Self {
+            common: COMMON_FIELDS
+                .iter()
+                .map(|(a, f)| (a.to_string(), f.to_string()))
+                .collect(),
+            data_types: HashMap::new(),
+        };
+        for (data_type, attribute, field) in DATA_TYPE_FIELDS {
+            me = me.with_field(data_type, attribute, field);
+        }
+        me
+    }
+}
+
+impl PlasoMapping {
+    /// stores `attribute` of all events of `data_type` in the ECS field `ecs_field`
+    pub fn with_field(mut self, data_type: &str, attribute: &str, ecs_field: &str) -> Self {
+        self.data_types
+            .entry(data_type.to_owned())
+            .or_default()
+            .insert(attribute.to_owned(), ecs_field.to_owned());
+        self
+    }
+
+    /// extends the default mapping by the mapping in a JSON file, which looks
+    /// like `{"<data_type>": {"<attribute>": "<ecs field>"}}`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let definition: Value = serde_json::from_reader(reader)?;
+        let data_types = definition
+            .as_object()
+            .ok_or_else(|| anyhow!("the plaso mapping must be a JSON object"))?;
+
+        let mut me = Self::default();
+        for (data_type, attributes) in data_types {
+            let attributes = attributes
+                .as_object()
+                .ok_or_else(|| anyhow!("the mapping of '{data_type}' must be a JSON object"))?;
+            for (attribute, field) in attributes {
+                match field.as_str() {
+                    Some(field) => me = me.with_field(data_type, attribute, field),
+                    None => bail!("invalid ECS field for '{data_type}.{attribute}': {field}"),
+                }
+            }
+        }
+        Ok(me)
+    }
+
+    /// returns the ECS field to store `attribute` of an event of `data_type` in
+    pub fn ecs_field(&self, data_type: &str, attribute: &str) -> Option<&str> {
+        self.data_types
+            .get(data_type)
+            .and_then(|attributes| attributes.get(attribute))
+            .or_else(|| self.common.get(attribute))
+            .map(|f| f.as_str())
+    }
+}
+
+/// An event which has been exported by plaso in `json_line` format
+pub struct PlasoEvent<'m> {
+    timestamp: Timestamp,
+    attributes: Map<String, Value>,
+    mapping: &'m PlasoMapping,
+}
+
+impl<'m> PlasoEvent<'m> {
+    pub fn new(event: Value, mapping: &'m PlasoMapping) -> Result<Self> {
+        let attributes = match event {
+            Value::Object(attributes) => attributes,
+            _ => bail!("plaso event must be a JSON object"),
+        };
+
+        // plaso stores timestamps as microseconds since the epoch, in UTC
+        let timestamp = attributes
+            .get("timestamp")
+            .and_then(|ts| ts.as_i64())
+            .ok_or_else(|| anyhow!("plaso event has no valid timestamp"))?;
+
+        Ok(Self {
+            timestamp: Timestamp::from_unix_micros(timestamp)?,
+            attributes,
+            mapping,
+        })
+    }
+
+    fn into_builder(self) -> Result<EcsBuilder> {
+        let data_type = self.attributes["data_type"].as_str().unwrap_or_default();
+        let message = match (self.attributes.get("message"), self.attributes.get("display_name")) {
+            (Some(Value::String(message)), _) => message.clone(),
+            (_, Some(Value::String(display_name))) => display_name.clone(),
+            _ => data_type.to_owned(),
+        };
+
+        let mut builder = EcsBuilder::new(message, self.timestamp.clone()).with_additional_tag("plaso");
+        if let Some(labels) = self.attributes.get("tag").and_then(|t| t["labels"].as_array()) {
+            for label in labels.iter().filter_map(|l| l.as_str()) {
+                builder = builder.with_additional_tag(label);
+            }
+        }
+
+        let mut unknown = Map::new();
+        for (attribute, value) in self.attributes.iter() {
+            if IGNORED_ATTRIBUTES.contains(&attribute.as_str()) || attribute == "tag" {
+                continue;
+            }
+            match self.mapping.ecs_field(data_type, attribute) {
+                // plaso uses '-' for unknown values
+                Some(_) if value.is_null() || value == &json!("-") => (),
+                Some(field) => builder = builder.with_field(field, value.clone())?,
+                None => {
+                    unknown.insert(attribute.clone(), value.clone());
+                }
+            }
+        }
+        if !unknown.is_empty() {
+            builder = builder.with_custom_fields("plaso", Value::Object(unknown))?;
+        }
+        Ok(builder)
+    }
+}
+
+impl TimelineObject for PlasoEvent<'_> {}
+
+impl IntoIterator for PlasoEvent<'_> {
+    type Item = anyhow::Result<EcsBuilder>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+    fn into_iter(self) -> Self::IntoIter {
+        vec![self.into_builder()].into_iter()
+    }
+}
@@ -15,7 +15,7 @@ impl SimpleEvent {
     pub fn documents(&self) -> impl Iterator<Item=Value> {
         let docs: HashMap<Timestamp, Value> = HashMap::new();
         docs.into_iter().map(|(ts, v)| {
-            add_to_json(&v, "|@timestamp|", Value::Number(ts.timestamp_millis().into()))
+            add_to_json(&v, "|@timestamp|", Value::from(&ts))
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,20 @@
+use serde::Serialize;
+
+use super::ecs_object::EcsObject;
+
+#[derive(Serialize)]
+pub struct User {
+    name: String,
+}
+
+impl EcsObject for User {
+    fn object_key(&self) -> &'static str {
+        "user"
+    }
+}
+
+impl From<String> for User {
+    fn from(name: String) -> Self {
+        Self { name }
+    }
+}
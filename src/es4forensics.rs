@@ -1,26 +1,33 @@
 mod cli;
-mod index;
-mod index_builder;
-mod timestamp;
-mod utils;
-mod ecs;
-mod protocol;
+mod dead_letter_file;
+mod import_options;
 mod stream_source;
 
-use std::io::BufRead;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::Duration;
 use anyhow::{Result, anyhow};
+use chrono::{DateTime, SecondsFormat, Utc};
 
-use cli::{Cli, Action};
-use elasticsearch::auth::Credentials;
-use index_builder::*;
-use protocol::*;
-use stream_source::StreamSource;
-use clap::Parser;
+use cli::{Cli, Action, IdStrategyName, KibanaArgs, ParserArgs, FilterArgs};
+use dead_letter_file::DeadLetterFile;
+use import_options::ImportOptions;
+use elasticsearch::{auth::Credentials, http::Url};
+use es4forensics::{
+    objects::PlasoMapping,
+    ClockCorrection, DstPolicy, EvidenceFile, ExportFormat, IdStrategy, ImportAudit,
+    ImportStatistics, Index, IndexBuilder, IndexInfo, IndexReader, InputDigest, InputFormat, Kibana,
+    MappingChecker, PathPattern, Pipeline, Provenance, TimelineFilter, TimelineParser,
+    TimelineQuery, TimelineSorter, TimelineWriter, WithHost, DEFAULT_COLUMNS,
+};
+use serde_json::Value;
+use stream_source::{SourceLine, SourceLines, SourceText, StreamSource};
+use clap::ValueEnum;
 use simplelog::{TermLogger, Config, ColorChoice, TerminalMode};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = Cli::parse_args();
 
     let _ = TermLogger::init(
         cli.verbose.log_level_filter(),
@@ -39,55 +46,577 @@ struct Es4Forensics {
 impl Es4Forensics {
     pub async fn run(self) -> Result<()> {
 
+        // this is the only subcommand which does not need elasticsearch
+        if let Action::Convert {
+            input_files, sort, sort_buffer, parser, output_format, output_file
+        } = &self.cli.action {
+            let sort_buffer = (*sort || input_files.len() > 1).then_some(*sort_buffer);
+            return self.convert(parser, input_files, sort_buffer, output_format, output_file);
+        }
+
         let builder = self.create_index_builder()?;
 
         match &self.cli.action {
-            Action::CreateIndex => {
+            Action::CreateIndex { data_view, kibana, aliases, write_aliases } => {
+                let builder = aliases
+                    .iter()
+                    .fold(builder, |b, alias| b.with_alias(alias.clone()));
+                let builder = write_aliases
+                    .iter()
+                    .fold(builder, |b, alias| b.with_write_alias(alias.clone()));
                 if builder.index_exists().await? {
-                    return Err(anyhow!("index '{}' exists already", self.cli.index_name));
+                    return Err(anyhow!("index '{}' exists already", self.index_name()));
                 }
                 builder.create_index().await?;
+                if *data_view {
+                    self.create_data_view(kibana).await?;
+                }
+                Ok(())
+            }
+            Action::CreateDataView { kibana } => self.create_data_view(kibana).await,
+            Action::Import{input_file, parser: parser_args, bulk_size, pipeline, id_strategy, id_fields, bulk_mode, dead_letter_file, provenance, dry_run} => {
+                let id_strategy = Self::id_strategy(id_strategy, id_fields)?;
+                // fail before the input is read, even in a dry run
+                bulk_mode.check_id_strategy(&id_strategy)?;
+                let mut parser = Self::create_parser(parser_args)?;
+                let format = parser.format().clone();
+                // every document contains the hash of the input, so it must be known before
+                // the input is imported. Hashing the input is expensive, so we do it only if we need to
+                let evidence = match provenance {
+                    true => Some(EvidenceFile::from_path(input_file)?),
+                    false => None,
+                };
+                let options = ImportOptions::new(*bulk_size)
+                    .with_dead_letter_file(dead_letter_file.clone())
+                    .with_provenance(evidence.as_ref().map(|e| Provenance::new(e, format.parser_name())));
+                if *dry_run {
+                    let lines = Self::source_lines(&format, StreamSource::from(input_file)?);
+                    return self.dry_run(&builder, &id_strategy, &mut parser, lines, &options).await;
+                }
+                let mut audit = ImportAudit::start(self.index_name().to_owned());
+                if format.uses_timezone() {
+                    audit = audit.with_timezone(parser_args.timezone.name().to_owned());
+                }
+                let builder = match pipeline {
+                    Some(p) => builder.with_pipeline(p.clone()),
+                    None => builder,
+                };
+                let builder = builder
+                    .with_id_strategy(id_strategy)
+                    .with_bulk_mode(bulk_mode.clone());
+                // the audit records the hash of the bytes which have actually been imported
+                let (source, digest) = StreamSource::hashed(input_file)?;
+                let lines = Self::source_lines(&format, source);
+                let result = self.import(&builder, &mut parser, lines, &options, &mut audit).await;
+                Self::finish_audit(&builder, audit, result, input_file, &digest, evidence.as_ref()).await
+            }
+            Action::Convert { .. } => unreachable!("convert has already been handled"),
+            Action::ListIndices => {
+                Self::print_indices(&builder.list_indices().await?);
                 Ok(())
             }
-            Action::Import{input_file, bulk_size} => {
-                let source = StreamSource::from(input_file)?;
-                self.import(builder, source.into(), *bulk_size).await
+            Action::Inspect => {
+                println!("aliases of '{}': {}", self.index_name(), builder.aliases().await?.join(", "));
+                println!("mapping of '{}':", self.index_name());
+                println!("{}", serde_json::to_string_pretty(&builder.mapping().await?)?);
+                match builder.sample_document().await? {
+                    None => println!("the index is empty"),
+                    Some(doc) => {
+                        println!("sample document:");
+                        println!("{}", serde_json::to_string_pretty(&doc)?);
+                    }
+                }
+                Ok(())
+            }
+            Action::DeleteIndex { yes } => {
+                if !builder.index_exists().await? {
+                    return Err(anyhow!("index '{}' does not exist", self.index_name()));
+                }
+                if !*yes && !Self::confirm_deletion(self.index_name())? {
+                    log::warn!("aborted; index '{}' has not been deleted", self.index_name());
+                    return Ok(());
+                }
+                builder.delete_index().await
+            }
+            Action::AttachAlias { alias, write_index } => {
+                builder.attach_to_alias(alias, *write_index).await
+            }
+            Action::DetachAlias { alias } => builder.detach_from_alias(alias).await,
+            Action::SetLifecycle { policy } => builder.set_lifecycle_policy(policy).await,
+            Action::Snapshot { repository, snapshot } => {
+                let snapshot = match snapshot {
+                    Some(s) => s.clone(),
+                    None => format!(
+                        "{}-{}",
+                        self.index_name().to_lowercase(),
+                        Utc::now().format("%Y%m%d%H%M%S")
+                    ),
+                };
+                builder.create_snapshot(repository, &snapshot).await
+            }
+            Action::RestoreSnapshot { repository, snapshot, rename_to } => {
+                builder
+                    .restore_snapshot(repository, snapshot, rename_to.as_deref())
+                    .await
+            }
+            Action::Export { format, output_file, from, to, query } => {
+                let mut timeline_query = TimelineQuery::default();
+                if let Some(from) = from {
+                    timeline_query = timeline_query.with_start((*from).into());
+                }
+                if let Some(to) = to {
+                    timeline_query = timeline_query.with_end((*to).into());
+                }
+                if let Some(query) = query {
+                    timeline_query = timeline_query.with_query_string(query.clone());
+                }
+                self.export(&builder, &timeline_query, format, output_file).await
+            }
+            Action::Search { from, to, around, window, query, hosts, tags, limit, format } => {
+                let mut timeline_query = TimelineQuery::default()
+                    .with_hosts(hosts.clone())
+                    .with_tags(tags.clone());
+                if let Some(around) = around {
+                    timeline_query = timeline_query.with_window_around(&(*around).into(), *window);
+                }
+                if let Some(from) = from {
+                    timeline_query = timeline_query.with_start((*from).into());
+                }
+                if let Some(to) = to {
+                    timeline_query = timeline_query.with_end((*to).into());
+                }
+                if let Some(query) = query {
+                    timeline_query = timeline_query.with_query_string(query.clone());
+                }
+                let mut writer = TimelineWriter::new(format.clone(), std::io::stdout())?;
+                for document in builder.search(&timeline_query, *limit).await? {
+                    writer.write_document(&document)?;
+                }
+                writer.flush()
+            }
+            Action::PutPipeline { name, definition_file, case, analyst } => {
+                let mut pipeline = match definition_file {
+                    Some(f) => Pipeline::from_file(name.clone(), f)?,
+                    None => Pipeline::with_id(name.clone()).with_ingest_timestamp(),
+                };
+                if let Some(case) = case {
+                    pipeline = pipeline.with_case(case);
+                }
+                if let Some(analyst) = analyst {
+                    pipeline = pipeline.with_analyst(analyst);
+                }
+                builder.put_pipeline(&pipeline).await
             }
         }
     }
 
-    async fn import(&self, builder: IndexBuilder, reader: Box<dyn BufRead + Send>, bulk_size: usize) -> Result<()> {
+    async fn import(
+        &self,
+        builder: &IndexBuilder,
+        parser: &mut TimelineParser,
+        lines: SourceLines,
+        options: &ImportOptions,
+        audit: &mut ImportAudit,
+    ) -> Result<()> {
         let mut index = builder.connect().await?;
-        index.set_cache_size(bulk_size).await?;
+        index.set_cache_size(options.bulk_size()).await?;
+        let dead_letter_file = options.dead_letter_file(self.index_name());
+        index.set_dead_letter(Box::new(DeadLetterFile::new(dead_letter_file)));
+
+        let mut statistics = ImportStatistics::default();
+        let mut result = self.import_documents(&mut index, parser, lines, &mut statistics, options.provenance()).await;
+        if result.is_ok() {
+            result = index.flush().await;
+        }
+        if index.rejected_documents() > 0 {
+            log::warn!("{} documents have been rejected because of mapping conflicts", index.rejected_documents());
+        }
+
+        audit.finish(&statistics, index.rejected_documents(), index.failed_documents());
+        result
+    }
+
+    /// records the input file in the audit and writes the audit, also if the import
+    /// has failed. The hash of the input is known only if it has been imported completely.
+    async fn finish_audit(
+        builder: &IndexBuilder,
+        mut audit: ImportAudit,
+        mut result: Result<()>,
+        input_file: &str,
+        digest: &InputDigest,
+        evidence: Option<&EvidenceFile>,
+    ) -> Result<()> {
+        if result.is_ok() {
+            let imported = EvidenceFile::from_digest(input_file, digest);
+            // the provenance of the documents refers to the hash which has been calculated before
+            if let Some(evidence) = evidence.filter(|e| e.sha256.is_some() && e.sha256 != imported.sha256) {
+                result = Err(anyhow!("input file '{}' has changed while it was imported", evidence.path));
+            }
+            audit.add_input_file(imported);
+        } else {
+            audit.add_input_file(EvidenceFile::without_hash(input_file));
+        }
+
+        if let Err(why) = &result {
+            audit.fail(why.to_string());
+        }
+        let audit_result = builder.write_audit(&audit).await;
+        result.and(audit_result)
+    }
+
+    async fn import_documents(
+        &self,
+        index: &mut Index,
+        parser: &mut TimelineParser,
+        lines: SourceLines,
+        statistics: &mut ImportStatistics,
+        provenance: Option<&Provenance>,
+    ) -> Result<()> {
+        for line in lines {
+            for value in self.parse_line(parser, &line?, statistics, provenance)? {
+                index.add_bulk_document(value).await?;
+            }
+        }
+        Ok(())
+    }
 
-        for line in reader.lines() {
+    /// converts a line of the input into documents and counts them. Lines which cannot be
+    /// parsed are skipped, unless strict mode is enabled.
+    fn parse_line(
+        &self,
+        parser: &mut TimelineParser,
+        line: &SourceLine,
+        statistics: &mut ImportStatistics,
+        provenance: Option<&Provenance>,
+    ) -> Result<Vec<Value>> {
+        statistics.lines += 1;
+        let filtered_documents = parser.filtered_documents();
+        let values = match &line.text {
+            SourceText::Line(text) => parser.parse_line(text),
+            SourceText::Record(record) => parser.parse_csv_record(record),
+        };
+        let mut values = match values {
+            Ok(values) => values,
+            Err(why) => {
+                if self.cli.strict_mode {
+                    return Err(anyhow!("error while parsing line {}: {}", line.number, why))
+                } else {
+                    log::error!("error while parsing line {}: {}", line.number, why);
+                    log::error!("failed line was:     {}", line.text);
+                    statistics.parse_errors += 1;
+                    return Ok(Vec::new());
+                }
+            }
+        };
+        statistics.filtered_documents += parser.filtered_documents() - filtered_documents;
+        if let Some(provenance) = provenance {
+            for value in values.iter_mut() {
+                provenance.apply(value, line.number, line.offset);
+            }
+        }
+        statistics.add_documents(&values);
+        Ok(values)
+    }
+
+    /// parses the input, generates document ids and checks all documents against the
+    /// mapping of the index, without sending any documents to elasticsearch
+    async fn dry_run(
+        &self,
+        builder: &IndexBuilder,
+        id_strategy: &IdStrategy,
+        parser: &mut TimelineParser,
+        lines: SourceLines,
+        options: &ImportOptions,
+    ) -> Result<()> {
+        let mapping = if builder.index_exists().await? {
+            builder.mapping().await?
+        } else {
+            log::info!("index '{}' does not exist, using the default mapping", self.index_name());
+            IndexBuilder::default_mappings()
+        };
+        let mut checker = MappingChecker::from_mapping(&mapping);
+        let mut statistics = ImportStatistics::default();
+
+        for line in lines {
             let line = line?;
-            let value = match serde_json::from_str(&line) {
-                Ok(v) => v,
-                Err(why) => {
-                    if self.cli.strict_mode {
-                        return Err(anyhow!(why))
-                    } else {
-                        log::error!("error while parsing: {}", why);
-                        log::error!("failed JSON was:     {}", line);
-                        continue;
+            for mut value in self.parse_line(parser, &line, &mut statistics, options.provenance())? {
+                // the index coerces documents before it generates their ids, so do we
+                let conflicts = checker.coerce(&mut value);
+                for conflict in conflicts.iter() {
+                    log::debug!("conflict in line {}: {}", line.number, conflict);
+                }
+                statistics.add_conflicts(conflicts);
+
+                match id_strategy.document_id(&value) {
+                    Ok(Some(id)) => statistics.add_id(&id),
+                    Ok(None) => (),
+                    Err(why) => {
+                        log::error!("error in line {}: {}", line.number, why);
+                        statistics.id_errors += 1;
                     }
                 }
+            }
+        }
+
+        print!("{statistics}");
+        Ok(())
+    }
+
+    async fn create_data_view(&self, args: &KibanaArgs) -> Result<()> {
+        let url = match &args.url {
+            Some(url) => url.clone(),
+            None => Url::parse(&format!("{}://{}:5601", self.cli.protocol, self.cli.host))?,
+        };
+        let mut kibana = Kibana::with_url(url)
+            .with_credentials(self.cli.username.clone(), self.cli.password().to_owned());
+        if let Some(space) = &args.space {
+            kibana = kibana.with_space(space.clone());
+        }
+        if self.cli.omit_certificate_validation {
+            kibana = kibana.without_certificate_validation();
+        }
+        if let Some(proxy) = &self.cli.proxy {
+            kibana = kibana.with_proxy(proxy.clone());
+            if let Some(username) = &self.cli.proxy_username {
+                kibana = kibana.with_proxy_credentials(
+                    username.clone(),
+                    self.cli.proxy_password.clone().unwrap_or_default(),
+                );
+            }
+        }
+        if let Some(timeout) = self.cli.timeout {
+            kibana = kibana.with_timeout(Duration::from_secs(timeout));
+        }
+
+        let data_view_id = kibana.create_data_view(self.index_name()).await?;
+        let title = format!("{} timeline", self.index_name());
+        kibana
+            .create_saved_search(&data_view_id, &title, &DEFAULT_COLUMNS)
+            .await?;
+        Ok(())
+    }
+
+    /// converts all input files into `output_format`. If `sort_buffer` is set, the
+    /// documents are sorted by `@timestamp`, keeping at most `sort_buffer` documents in memory.
+    fn convert(
+        &self,
+        parser_args: &ParserArgs,
+        input_files: &[String],
+        sort_buffer: Option<usize>,
+        output_format: &ExportFormat,
+        output_file: &str,
+    ) -> Result<()> {
+        let output: Box<dyn Write> = if output_file == "-" {
+            Box::new(std::io::stdout())
+        } else {
+            Box::new(BufWriter::new(File::create(output_file)?))
+        };
+        let mut writer = TimelineWriter::new(output_format.clone(), output)?;
+        let mut sorter = sort_buffer.map(TimelineSorter::new);
+        let mut statistics = ImportStatistics::default();
+        for input_file in input_files {
+            let (format, path) = Self::split_input_format(input_file);
+            let mut parser = match format {
+                Some(format) => Self::create_parser(parser_args)?.with_format(format),
+                None => Self::create_parser(parser_args)?,
             };
+            let lines = Self::source_lines(parser.format(), StreamSource::from(path)?);
+            for line in lines {
+                for value in self.parse_line(&mut parser, &line?, &mut statistics, None)? {
+                    match sorter.as_mut() {
+                        Some(sorter) => sorter.add(value)?,
+                        None => writer.write_document(&value)?,
+                    }
+                }
+            }
+        }
+        if let Some(sorter) = sorter {
+            log::info!("merging {} sorted temporary files", sorter.runs());
+            for value in sorter.into_sorted()? {
+                writer.write_document(&value?)?;
+            }
+        }
+        writer.flush()?;
+        log::info!(
+            "converted {} lines into {} documents ({} parse errors)",
+            statistics.lines, statistics.documents, statistics.parse_errors
+        );
+        Ok(())
+    }
+
+    fn source_lines(format: &InputFormat, source: StreamSource) -> SourceLines {
+        if format.is_csv() {
+            SourceLines::csv(source)
+        } else {
+            source.into()
+        }
+    }
 
-            index.add_bulk_document(value).await?;
+    /// splits an input file like `l2tcsv:timeline.csv` into its format and its path
+    fn split_input_format(input_file: &str) -> (Option<InputFormat>, &str) {
+        if let Some((format, path)) = input_file.split_once(':') {
+            if let Ok(format) = InputFormat::from_str(format, true) {
+                return (Some(format), path);
+            }
         }
-        index.flush().await?;
+        (None, input_file)
+    }
+
+    async fn export(
+        &self,
+        builder: &IndexBuilder,
+        query: &TimelineQuery,
+        format: &ExportFormat,
+        output_file: &str,
+    ) -> Result<()> {
+        let output: Box<dyn Write> = if output_file == "-" {
+            Box::new(std::io::stdout())
+        } else {
+            Box::new(BufWriter::new(File::create(output_file)?))
+        };
+        let mut writer = TimelineWriter::new(format.clone(), output)?;
+        let mut reader = builder.open_reader(query).await?;
+        let result = Self::export_documents(&mut reader, &mut writer).await;
+
+        // release the point in time, even if reading the documents failed
+        let closed = reader.close().await;
+        let document_count = result?;
+        closed?;
+        log::info!("exported {document_count} documents");
         Ok(())
-    }    
+    }
+
+    async fn export_documents<W: Write>(
+        reader: &mut IndexReader<'_>,
+        writer: &mut TimelineWriter<W>,
+    ) -> Result<usize> {
+        let mut document_count = 0;
+        while let Some(documents) = reader.next_page().await? {
+            for document in documents {
+                writer.write_document(&document)?;
+                document_count += 1;
+            }
+        }
+        writer.flush()?;
+        Ok(document_count)
+    }
+
+    fn print_indices(indices: &[IndexInfo]) {
+        let format_ts = |ts: &Option<DateTime<Utc>>| match ts {
+            Some(ts) => ts.to_rfc3339_opts(SecondsFormat::Secs, true),
+            None => "-".to_owned(),
+        };
+        let width = indices.iter().map(|i| i.name.len()).max().unwrap_or(0).max(5);
+        println!(
+            "{:width$}  {:>6}  {:>12}  {:>10}  {:20}  {:20}",
+            "index", "status", "documents", "size", "first timestamp", "last timestamp"
+        );
+        for index in indices {
+            println!(
+                "{:width$}  {:>6}  {:>12}  {:>10}  {:20}  {:20}",
+                index.name,
+                index.status,
+                index.docs_count,
+                index.store_size,
+                format_ts(&index.first_timestamp),
+                format_ts(&index.last_timestamp)
+            );
+        }
+    }
+
+    fn confirm_deletion(index_name: &str) -> Result<bool> {
+        eprint!("type the name of the index to confirm its deletion: ");
+        std::io::stderr().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        Ok(answer.trim() == index_name)
+    }
+
+    fn index_name(&self) -> &str {
+        self.cli.index_name()
+    }
+
+    fn id_strategy(name: &IdStrategyName, fields: &[String]) -> Result<IdStrategy> {
+        Ok(match name {
+            IdStrategyName::ContentHash => IdStrategy::ContentHash,
+            IdStrategyName::Auto => IdStrategy::Auto,
+            IdStrategyName::FieldHash => {
+                if fields.is_empty() {
+                    return Err(anyhow!("the field-hash id strategy requires --id-fields"));
+                }
+                IdStrategy::FieldHash(fields.to_vec())
+            }
+            IdStrategyName::FromField => match fields {
+                [field] => IdStrategy::FromField(field.clone()),
+                _ => return Err(anyhow!("the from-field id strategy requires exactly one field in --id-fields")),
+            },
+        })
+    }
+
+    fn create_parser(args: &ParserArgs) -> Result<TimelineParser> {
+        let dst_policy = DstPolicy::default()
+            .with_ambiguous(args.ambiguous_time)
+            .with_nonexistent(args.nonexistent_time);
+        let mut parser = TimelineParser::new(args.format.clone())
+            .with_timezone(args.timezone)
+            .with_dst_policy(dst_policy)
+            .with_timestamp_format(args.timestamp_format);
+        if let Some(plaso_mapping) = &args.plaso_mapping {
+            parser = parser.with_plaso_mapping(PlasoMapping::from_file(plaso_mapping)?);
+        }
+        if args.clock_offset.is_some() || args.clock_offsets.is_some() {
+            let mut clock_correction = ClockCorrection::default();
+            if let Some(offset) = args.clock_offset {
+                clock_correction = clock_correction.with_offset(offset);
+            }
+            if let Some(clock_offsets) = &args.clock_offsets {
+                clock_correction = clock_correction.with_host_offsets_from(clock_offsets)?;
+            }
+            parser = parser.with_clock_correction(clock_correction);
+        }
+        if let Some(filter) = Self::create_filter(&args.filter)? {
+            parser = parser.with_filter(filter);
+        }
+        Ok(parser)
+    }
+
+    fn create_filter(args: &FilterArgs) -> Result<Option<TimelineFilter>> {
+        let mut filter = TimelineFilter::default().with_skip_empty_timestamps(args.skip_empty_timestamps);
+        let mut is_active = args.skip_empty_timestamps;
+        if let Some(from) = args.from {
+            filter = filter.with_start(from.into());
+            is_active = true;
+        }
+        if let Some(to) = args.to {
+            filter = filter.with_end(to.into());
+            is_active = true;
+        }
+        for pattern in &args.include_paths {
+            filter = filter.with_include_path(PathPattern::glob(pattern)?);
+            is_active = true;
+        }
+        for pattern in &args.include_path_regexes {
+            filter = filter.with_include_path(PathPattern::regex(pattern)?);
+            is_active = true;
+        }
+        for pattern in &args.exclude_paths {
+            filter = filter.with_exclude_path(PathPattern::glob(pattern)?);
+            is_active = true;
+        }
+        for pattern in &args.exclude_path_regexes {
+            filter = filter.with_exclude_path(PathPattern::regex(pattern)?);
+            is_active = true;
+        }
+        Ok(is_active.then_some(filter))
+    }
 
     fn create_index_builder(&self) -> Result<IndexBuilder> {
-        let mut builder = IndexBuilder::with_name(self.cli.index_name.clone())
+        let mut builder = IndexBuilder::with_name(self.index_name().to_owned())
             .with_host(self.cli.host.clone())
             .with_port(self.cli.port)
             .with_credentials(Credentials::Basic(
                 self.cli.username.clone(),
-                self.cli.password.clone(),
+                self.cli.password().to_owned(),
             ))
             .with_protocol(self.cli.protocol.clone());
 
@@ -96,6 +625,24 @@ impl Es4Forensics {
             builder = builder.without_certificate_validation();
         }
 
+        if let Some(proxy) = &self.cli.proxy {
+            builder = builder.with_proxy(proxy.clone());
+            if let Some(username) = &self.cli.proxy_username {
+                builder = builder.with_proxy_credentials(
+                    username.clone(),
+                    self.cli.proxy_password.clone().unwrap_or_default(),
+                );
+            }
+        }
+
+        if let Some(timeout) = self.cli.timeout {
+            builder = builder.with_timeout(Duration::from_secs(timeout));
+        }
+
+        if let Some(timeout) = self.cli.request_timeout {
+            builder = builder.with_request_timeout(Duration::from_secs(timeout));
+        }
+
         Ok(builder)
     }
 }
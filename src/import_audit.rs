@@ -0,0 +1,255 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, Read},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::ImportStatistics;
+
+/// An input file of an import, identified by its SHA-256 hash
+pub struct EvidenceFile {
+    pub path: String,
+
+    /// size and hash are unknown if the data has been read from stdin
+    pub size: Option<u64>,
+    pub sha256: Option<String>,
+}
+
+impl EvidenceFile {
+    /// reads the whole file (as it is stored, i.e. still compressed) to calculate its hash
+    pub fn from_path(path: &str) -> Result<Self> {
+        if path == "-" {
+            return Ok(Self {
+                path: path.to_owned(),
+                size: None,
+                sha256: None,
+            });
+        }
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut hasher = Sha256::new();
+        let size = io::copy(&mut reader, &mut hasher)?;
+        Ok(Self {
+            path: Self::canonical_path(path),
+            size: Some(size),
+            sha256: Some(format!("{:x}", hasher.finalize())),
+        })
+    }
+
+    /// uses the size and the hash of all bytes which have been read through `digest`
+    pub fn from_digest(path: &str, digest: &InputDigest) -> Self {
+        let (size, sha256) = digest.finish();
+        Self {
+            path: Self::canonical_path(path),
+            size: Some(size),
+            sha256: Some(sha256),
+        }
+    }
+
+    /// only records the path, because the file has not been read completely
+    pub fn without_hash(path: &str) -> Self {
+        Self {
+            path: Self::canonical_path(path),
+            size: None,
+            sha256: None,
+        }
+    }
+
+    fn canonical_path(path: &str) -> String {
+        if path == "-" {
+            return path.to_owned();
+        }
+        match Path::new(path).canonicalize() {
+            Ok(p) => p.to_string_lossy().into_owned(),
+            Err(_) => path.to_owned(),
+        }
+    }
+}
+
+/// Calculates the SHA-256 hash and the size of an input while it is being imported,
+/// so that the input needs to be read only once and the hash covers exactly the
+/// bytes which have been read
+///
+/// ```
+/// use std::io::Read;
+/// use es4forensics::InputDigest;
+///
+/// let digest = InputDigest::default();
+/// let mut content = String::new();
+/// digest.reader("abc".as_bytes()).read_to_string(&mut content).unwrap();
+/// let (size, sha256) = digest.finish();
+/// assert_eq!(size, 3);
+/// assert!(sha256.starts_with("ba7816bf"));
+/// ```
+#[derive(Clone, Default)]
+pub struct InputDigest(Arc<Mutex<(Sha256, u64)>>);
+
+impl InputDigest {
+    /// wraps `reader`, so that all bytes which are read from it are hashed
+    pub fn reader<R: Read>(&self, reader: R) -> HashingReader<R> {
+        HashingReader {
+            reader,
+            digest: self.clone(),
+        }
+    }
+
+    /// returns the size and the hex encoded hash of all bytes which have been read so far
+    pub fn finish(&self) -> (u64, String) {
+        let state = self.0.lock().unwrap();
+        (state.1, format!("{:x}", state.0.clone().finalize()))
+    }
+}
+
+/// Reader which passes all bytes to an [`InputDigest`]
+pub struct HashingReader<R> {
+    reader: R,
+    digest: InputDigest,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.reader.read(buf)?;
+        let mut state = self.digest.0.lock().unwrap();
+        state.0.update(&buf[..count]);
+        state.1 += count as u64;
+        Ok(count)
+    }
+}
+
+/// Metadata about an import run, which is stored in the companion
+/// `<index>-audit` index to document the chain of custody
+pub struct ImportAudit {
+    index_name: String,
+    operator: String,
+    hostname: String,
+    started: DateTime<Utc>,
+    finished: Option<DateTime<Utc>>,
+    input_files: Vec<EvidenceFile>,
+    timezone: Option<String>,
+    counts: Value,
+    error: Option<String>,
+}
+
+impl ImportAudit {
+    /// starts auditing an import into `index_name`, by the current user on the current host
+    pub fn start(index_name: String) -> Self {
+        Self {
+            index_name,
+            operator: whoami::username(),
+            hostname: whoami::fallible::hostname().unwrap_or_default(),
+            started: Utc::now(),
+            finished: None,
+            input_files: Vec::new(),
+            timezone: None,
+            counts: json!({}),
+            error: None,
+        }
+    }
+
+    pub fn with_input_file(mut self, input_file: EvidenceFile) -> Self {
+        self.input_files.push(input_file);
+        self
+    }
+
+    /// records an input file whose hash is known only after it has been imported
+    pub fn add_input_file(&mut self, input_file: EvidenceFile) {
+        self.input_files.push(input_file);
+    }
+
+    /// records the timezone which has been used to interpret local timestamps
+    pub fn with_timezone(mut self, timezone: String) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// records the end of the import run and its final counts
+    pub fn finish(&mut self, statistics: &ImportStatistics, rejected: u64, failed: u64) {
+        self.finished = Some(Utc::now());
+        self.counts = json!({
+            "lines": statistics.lines,
+            "parse_errors": statistics.parse_errors,
+            "documents": statistics.documents,
+            "filtered": statistics.filtered_documents,
+            "ambiguous_lines": statistics.ambiguous_lines,
+            "shifted_lines": statistics.shifted_lines,
+            "rejected": rejected,
+            "failed": failed,
+            "imported": statistics.documents.saturating_sub(rejected + failed),
+        });
+    }
+
+    /// records that the import run has been aborted because of `error`
+    pub fn fail(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    pub fn to_document(&self) -> Value {
+        let format_ts = |ts: &DateTime<Utc>| ts.to_rfc3339_opts(SecondsFormat::Millis, true);
+        let input_files: Vec<Value> = self
+            .input_files
+            .iter()
+            .map(|f| json!({"path": f.path, "size": f.size, "hash": {"sha256": f.sha256}}))
+            .collect();
+        json!({
+            "@timestamp": format_ts(&self.started),
+            "message": format!("import into '{}'", self.index_name),
+            "event": {
+                "kind": "event",
+                "action": "import",
+                "start": format_ts(&self.started),
+                "end": self.finished.as_ref().map(format_ts),
+                "outcome": if self.error.is_none() { "success" } else { "failure" },
+                "reason": self.error,
+            },
+            "agent": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "user": {"name": self.operator},
+            "host": {"hostname": self.hostname},
+            "forensics": {
+                "index": self.index_name,
+                "status": if self.error.is_none() { "completed" } else { "failed" },
+                "timezone": self.timezone,
+                "input_files": input_files,
+                "counts": self.counts,
+            }
+        })
+    }
+}
+
+#[cfg(feature = "elasticsearch")]
+mod audit_index {
+    use anyhow::Result;
+    use elasticsearch::IndexParts;
+
+    use super::ImportAudit;
+    use crate::index_builder::{check_response, with_request_timeout, IndexBuilder};
+
+    impl IndexBuilder {
+        /// name of the companion index which stores the audit documents of this index
+        pub fn audit_index_name(&self) -> String {
+            format!("{}-audit", self.index_name())
+        }
+
+        /// stores the audit document of an import run in the audit index
+        pub async fn write_audit(&self, audit: &ImportAudit) -> Result<()> {
+            let client = self.create_client()?;
+            let audit_index = self.audit_index_name();
+            let request = client
+                .index(IndexParts::Index(&audit_index))
+                .body(audit.to_document());
+            let response = with_request_timeout!(request, self.request_timeout())
+                .send()
+                .await?;
+            check_response(response, "writing the audit document").await?;
+            log::info!("wrote audit document into '{audit_index}'");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use es4forensics::Provenance;
+
+/// Settings of an import which do not concern the parsing of the input
+pub(crate) struct ImportOptions {
+    bulk_size: usize,
+    dead_letter_file: Option<String>,
+    provenance: Option<Provenance>,
+}
+
+impl ImportOptions {
+    pub fn new(bulk_size: usize) -> Self {
+        Self {
+            bulk_size,
+            dead_letter_file: None,
+            provenance: None,
+        }
+    }
+
+    /// writes rejected documents into `dead_letter_file` instead of
+    /// `<INDEX_NAME>-dead-letter.jsonl`
+    pub fn with_dead_letter_file(mut self, dead_letter_file: Option<String>) -> Self {
+        self.dead_letter_file = dead_letter_file;
+        self
+    }
+
+    /// adds the provenance of its record to every document
+    pub fn with_provenance(mut self, provenance: Option<Provenance>) -> Self {
+        self.provenance = provenance;
+        self
+    }
+
+    pub fn bulk_size(&self) -> usize {
+        self.bulk_size
+    }
+
+    /// returns the file which receives rejected documents of `index_name`
+    pub fn dead_letter_file(&self, index_name: &str) -> String {
+        match &self.dead_letter_file {
+            Some(f) => f.to_owned(),
+            None => format!("{index_name}-dead-letter.jsonl"),
+        }
+    }
+
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.as_ref()
+    }
+}
@@ -0,0 +1,108 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashSet},
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+
+use serde_json::{json, Value};
+
+use crate::{DstAdjustment, FieldConflict};
+
+/// Counters which are collected while importing (or dry-running the import of) a timeline
+#[derive(Default)]
+pub struct ImportStatistics {
+    /// number of lines (or records) read from the input
+    pub lines: u64,
+
+    /// number of documents which have been generated from the input
+    pub documents: u64,
+
+    /// number of documents which have been dropped by the timeline filter
+    pub filtered_documents: u64,
+
+    /// number of lines which could not be parsed
+    pub parse_errors: u64,
+
+    /// number of documents for which no id could be generated
+    pub id_errors: u64,
+
+    /// number of lines with a timestamp which was ambiguous because of a DST transition
+    pub ambiguous_lines: u64,
+
+    /// number of lines with a timestamp which has been shifted out of a DST gap
+    pub shifted_lines: u64,
+
+    /// number of documents whose id has been generated for another document before
+    pub duplicate_ids: u64,
+
+    /// number of documents with at least one value which conflicts with the mapping
+    pub conflicting_documents: u64,
+
+    /// number of conflicts per field, together with the first conflict found
+    pub conflicts: BTreeMap<String, (u64, FieldConflict)>,
+
+    // we only store hashes of the ids, because there might be millions of them
+    seen_ids: HashSet<u64>,
+}
+
+impl ImportStatistics {
+    /// counts the documents which have been generated from one line (or record) of the
+    /// input. The line is counted once if any of them is tagged with a DST adjustment,
+    /// because all documents of a line usually contain the same timestamps.
+    pub fn add_documents(&mut self, documents: &[Value]) {
+        self.documents += documents.len() as u64;
+        let has_tag = |tag: DstAdjustment| {
+            documents.iter().any(|document| {
+                document["tags"]
+                    .as_array()
+                    .is_some_and(|tags| tags.contains(&json!(tag.tag())))
+            })
+        };
+        if has_tag(DstAdjustment::Ambiguous) {
+            self.ambiguous_lines += 1;
+        }
+        if has_tag(DstAdjustment::Shifted) {
+            self.shifted_lines += 1;
+        }
+    }
+
+    /// counts `id` as duplicate if it has already been seen
+    pub fn add_id(&mut self, id: &str) {
+        let mut hasher = DefaultHasher::new();
+        id.hash(&mut hasher);
+        if !self.seen_ids.insert(hasher.finish()) {
+            self.duplicate_ids += 1;
+        }
+    }
+
+    pub fn add_conflicts(&mut self, conflicts: Vec<FieldConflict>) {
+        if conflicts.is_empty() {
+            return;
+        }
+        self.conflicting_documents += 1;
+        for conflict in conflicts {
+            self.conflicts
+                .entry(conflict.field.clone())
+                .and_modify(|(count, _)| *count += 1)
+                .or_insert((1, conflict));
+        }
+    }
+}
+
+impl Display for ImportStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "lines read:             {:>12}", self.lines)?;
+        writeln!(f, "parse errors:           {:>12}", self.parse_errors)?;
+        writeln!(f, "documents:              {:>12}", self.documents)?;
+        writeln!(f, "filtered documents:     {:>12}", self.filtered_documents)?;
+        writeln!(f, "ambiguous time lines:   {:>12}", self.ambiguous_lines)?;
+        writeln!(f, "shifted time lines:     {:>12}", self.shifted_lines)?;
+        writeln!(f, "id errors:              {:>12}", self.id_errors)?;
+        writeln!(f, "duplicate ids:          {:>12}", self.duplicate_ids)?;
+        writeln!(f, "conflicting documents:  {:>12}", self.conflicting_documents)?;
+        for (count, conflict) in self.conflicts.values() {
+            writeln!(f, "  {count:>8} x {conflict}")?;
+        }
+        Ok(())
+    }
+}
@@ -1,32 +1,60 @@
+use std::{io::Write, time::Duration};
+
 use anyhow::{bail, Result};
-use base64::{encode_config, URL_SAFE_NO_PAD};
 use elasticsearch::{
     BulkOperation, BulkParts, Elasticsearch,
 };
-use serde_json::Value;
-use sha2::{Digest, Sha256};
+use serde_json::{json, Value};
 
-use crate::ecs::TimelineObject;
+use crate::{ecs::TimelineObject, IdStrategy, MappingChecker};
 
-struct ElasticDocument {
-    id: String,
-    content: Value,
+/// Specifies which bulk operation is used to write documents
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum BulkMode {
+    /// add new documents, but never overwrite existing documents
+    #[default]
+    Create,
+
+    /// add new documents and replace existing documents with the same id
+    Index,
+
+    /// merge documents into existing documents with the same id, or add them if they do not exist
+    Update,
 }
 
-impl From<ElasticDocument> for (String, Value) {
-    fn from(me: ElasticDocument) -> Self {
-        (me.id, me.content)
+impl BulkMode {
+    /// fails if documents cannot be written in this mode with the ids of `id_strategy`:
+    /// updating documents requires ids, which [`IdStrategy::Auto`] does not generate
+    pub fn check_id_strategy(&self, id_strategy: &IdStrategy) -> Result<()> {
+        if matches!(self, BulkMode::Update) && matches!(id_strategy, IdStrategy::Auto) {
+            bail!("updating documents requires document ids, which the 'auto' id strategy does not generate");
+        }
+        Ok(())
     }
 }
 
-impl From<Value> for ElasticDocument {
-    fn from(val: Value) -> Self {
-        let mut hasher = Sha256::new();
-        hasher.update(val.to_string());
-        let result = hasher.finalize();
-        Self {
-            id: encode_config(result, URL_SAFE_NO_PAD),
-            content: val,
+struct ElasticDocument {
+    id: Option<String>,
+    content: Value,
+}
+
+impl ElasticDocument {
+    fn into_operation(self, mode: &BulkMode) -> BulkOperation<Value> {
+        match (mode, self.id) {
+            (BulkMode::Create, Some(id)) => BulkOperation::create(id, self.content).into(),
+            (BulkMode::Index, Some(id)) => BulkOperation::index(self.content).id(id).into(),
+            (BulkMode::Create, None) | (BulkMode::Index, None) => {
+                BulkOperation::index(self.content).into()
+            }
+            (BulkMode::Update, Some(id)) => BulkOperation::update(
+                id,
+                json!({"doc": self.content, "doc_as_upsert": true}),
+            )
+            .into(),
+            (BulkMode::Update, None) => {
+                unreachable!("the id strategy has been checked by Index::set_id_strategy_and_bulk_mode")
+            }
         }
     }
 }
@@ -37,6 +65,18 @@ pub struct Index {
 
     cache_size: usize,
     document_cache: Option<Vec<ElasticDocument>>,
+    request_timeout: Option<Duration>,
+    pipeline: Option<String>,
+    id_strategy: IdStrategy,
+    bulk_mode: BulkMode,
+
+    /// checks outgoing documents against the mapping of the index, if configured
+    mapping_checker: Option<MappingChecker>,
+
+    /// receives all documents which have been rejected because of mapping conflicts
+    dead_letter: Option<Box<dyn Write + Send>>,
+    rejected_documents: u64,
+    failed_documents: u64,
 }
 
 impl Index {
@@ -46,6 +86,14 @@ impl Index {
             client,
             cache_size: 10000,
             document_cache: Some(Vec::new()),
+            request_timeout: None,
+            pipeline: None,
+            id_strategy: IdStrategy::default(),
+            bulk_mode: BulkMode::default(),
+            mapping_checker: None,
+            dead_letter: None,
+            rejected_documents: 0,
+            failed_documents: 0,
         }
     }
     
@@ -65,9 +113,55 @@ impl Index {
         Ok(())
     }
 
-    pub (crate) async fn add_bulk_document(&mut self, document: Value) -> Result<()> {
+    pub async fn add_bulk_document(&mut self, mut document: Value) -> Result<()> {
+        // the id must not depend on the mapping of the target index, so it is
+        // calculated before the document is coerced
+        let id = self.id_strategy.document_id(&document)?;
+        if !self.check_mapping(&mut document)? {
+            return Ok(());
+        }
+        self.add_document(id, document).await
+    }
+
+    /// adds a document with an id chosen by the caller, regardless of the configured [`IdStrategy`]
+    pub async fn add_bulk_document_with_id(&mut self, mut document: Value, id: String) -> Result<()> {
+        if !self.check_mapping(&mut document)? {
+            return Ok(());
+        }
+        self.add_document(Some(id), document).await
+    }
+
+    /// coerces the document to the mapping of the index, and returns `false` if
+    /// this was not possible and the document has been written to the dead letter.
+    /// Without a dead letter, rejecting a document is an error.
+    fn check_mapping(&mut self, document: &mut Value) -> Result<bool> {
+        let conflicts = match self.mapping_checker.as_mut() {
+            None => return Ok(true),
+            Some(checker) => checker.coerce(document),
+        };
+        if conflicts.is_empty() {
+            return Ok(true);
+        }
+
+        let reasons: Vec<String> = conflicts.iter().map(|c| c.to_string()).collect();
+        match self.dead_letter.as_mut() {
+            Some(dead_letter) => {
+                log::debug!("rejecting document: {}", reasons.join("; "));
+                serde_json::to_writer(&mut *dead_letter, &json!({
+                    "reasons": reasons,
+                    "document": document
+                }))?;
+                writeln!(dead_letter)?;
+            }
+            None => bail!("document {document} conflicts with the mapping of the index: {}", reasons.join("; ")),
+        }
+        self.rejected_documents += 1;
+        Ok(false)
+    }
+
+    async fn add_document(&mut self, id: Option<String>, content: Value) -> Result<()> {
         if let Some(c) = self.document_cache.as_mut() {
-            c.push(document.into())
+            c.push(ElasticDocument { id, content })
         }
 
         if self.document_cache.as_ref().unwrap().len() >= self.cache_size {
@@ -78,6 +172,9 @@ impl Index {
     }
 
     pub async fn flush(&mut self) -> Result<()> {
+        if let Some(dead_letter) = self.dead_letter.as_mut() {
+            dead_letter.flush()?;
+        }
 
         match self.document_cache.as_ref() {
             None => log::trace!("There is no document cache"),
@@ -90,17 +187,20 @@ impl Index {
                     let parts = BulkParts::Index(&self.name);
 
                     let item_count = self.document_cache.as_ref().unwrap().len();
-                    let items: Vec<BulkOperation<Value>> = self
+                    let items = self
                         .document_cache
                         .replace(Vec::new())
                         .unwrap()
                         .into_iter()
-                        .map(|v| {
-                            let (id, val) = v.into();
-                            BulkOperation::create(id, val).into()
-                        })
-                        .collect();
-                    let bulk = self.client.bulk(parts).body(items);
+                        .map(|d| d.into_operation(&self.bulk_mode))
+                        .collect::<Vec<BulkOperation<Value>>>();
+                    let mut bulk = self.client.bulk(parts).body(items);
+                    if let Some(timeout) = self.request_timeout {
+                        bulk = bulk.request_timeout(timeout);
+                    }
+                    if let Some(pipeline) = self.pipeline.as_ref() {
+                        bulk = bulk.pipeline(pipeline);
+                    }
 
                     let response = bulk.send().await?;
 
@@ -115,6 +215,13 @@ impl Index {
                         let json: Value = response.json().await?;
                         if json["errors"].as_bool().unwrap() {
                             log::error!("error while writing to elasticsearch: {json}");
+                            self.failed_documents += json["items"]
+                                .as_array()
+                                .into_iter()
+                                .flatten()
+                                .filter_map(|item| item.as_object()?.values().next())
+                                .filter(|result| result.get("error").is_some())
+                                .count() as u64;
                         } else {
                             log::trace!("successfully wrote {item_count} items");
                         }
@@ -132,10 +239,54 @@ impl Index {
         self.cache_size = cache_size;
         Ok(())
     }
+
+    pub fn set_request_timeout(&mut self, request_timeout: Option<Duration>) {
+        self.request_timeout = request_timeout;
+    }
+
+    pub fn set_pipeline(&mut self, pipeline: Option<String>) {
+        self.pipeline = pipeline;
+    }
+
+    /// sets how document ids are generated and how documents are written, see
+    /// [`BulkMode::check_id_strategy`] for the combinations which are not supported
+    pub fn set_id_strategy_and_bulk_mode(&mut self, id_strategy: IdStrategy, bulk_mode: BulkMode) -> Result<()> {
+        bulk_mode.check_id_strategy(&id_strategy)?;
+        self.id_strategy = id_strategy;
+        self.bulk_mode = bulk_mode;
+        Ok(())
+    }
+
+    /// checks every document against the mapping before it is sent to elasticsearch.
+    /// Documents which cannot be coerced to the mapping are rejected.
+    pub fn set_mapping_checker(&mut self, mapping_checker: Option<MappingChecker>) {
+        self.mapping_checker = mapping_checker;
+    }
+
+    /// writes rejected documents, together with the reasons for rejecting them, as JSON lines
+    /// into `dead_letter`. If no dead letter has been set, rejecting a document fails.
+    pub fn set_dead_letter(&mut self, dead_letter: Box<dyn Write + Send>) {
+        self.dead_letter = Some(dead_letter);
+    }
+
+    /// returns the number of documents which have been rejected because of mapping conflicts
+    pub fn rejected_documents(&self) -> u64 {
+        self.rejected_documents
+    }
+
+    /// returns the number of documents which elasticsearch failed to write
+    pub fn failed_documents(&self) -> u64 {
+        self.failed_documents
+    }
 }
 
 impl Drop for Index {
     fn drop(&mut self) {
-        let _ = self.flush();
+        // we cannot await [`Index::flush`] here, so all we can do is to warn the user
+        if let Some(c) = self.document_cache.as_ref() {
+            if !c.is_empty() {
+                log::warn!("dropping index with {} unflushed documents", c.len());
+            }
+        }
     }
 }
@@ -1,18 +1,21 @@
+use std::time::Duration;
+
 use anyhow::{anyhow, Result};
 use elasticsearch::{
     auth::Credentials,
-    cat::CatIndicesParts,
     cert::CertificateValidation,
     http::{
+        response::Response,
         transport::{SingleNodeConnectionPool, TransportBuilder},
         Url,
     },
-    indices::IndicesCreateParts,
+    indices::{IndicesCreateParts, IndicesExistsParts},
+    ingest::IngestPutPipelineParts,
     Elasticsearch,
 };
 use serde_json::{json, Value};
 
-use crate::{Protocol, index::Index};
+use crate::{Protocol, index::{Index, BulkMode}, Pipeline, IdStrategy, MappingChecker};
 
 pub struct IndexBuilder {
     host: Option<String>,
@@ -21,6 +24,41 @@ pub struct IndexBuilder {
     index_name: String,
     do_certificate_validation: bool,
     credentials: Option<Credentials>,
+    proxy: Option<Url>,
+    proxy_credentials: Option<(String, String)>,
+    timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    pipeline: Option<String>,
+    id_strategy: IdStrategy,
+    bulk_mode: BulkMode,
+
+    /// aliases which the index will be added to when it is created, and whether
+    /// the index shall become the write index of the alias
+    aliases: Vec<(String, bool)>,
+}
+
+/// applies the configured request timeout (if any) to an API request
+macro_rules! with_request_timeout {
+    ($request: expr, $timeout: expr) => {
+        match $timeout {
+            Some(timeout) => $request.request_timeout(timeout),
+            None => $request,
+        }
+    };
+}
+pub(crate) use with_request_timeout;
+
+/// returns the response if it has a success status code, or logs the error
+/// message sent by elasticsearch and fails otherwise
+pub(crate) async fn check_response(response: Response, action: &str) -> Result<Response> {
+    match response.error_for_status_code_ref() {
+        Ok(_) => Ok(response),
+        Err(why) => {
+            log::error!("Error while {action}: {}", response.text().await?);
+            log::error!("error message was: {}", why);
+            Err(anyhow!(why))
+        }
+    }
 }
 
 const DEFAULT_HOST: &str = "localhost";
@@ -39,6 +77,14 @@ impl IndexBuilder {
             index_name,
             do_certificate_validation: true,
             credentials: None,
+            proxy: None,
+            proxy_credentials: None,
+            timeout: None,
+            request_timeout: None,
+            pipeline: None,
+            id_strategy: IdStrategy::default(),
+            bulk_mode: BulkMode::default(),
+            aliases: Vec::new(),
         }
     }
 
@@ -62,6 +108,66 @@ impl IndexBuilder {
         self
     }
 
+    /// sends all requests through the HTTP proxy at `proxy`. If no proxy is
+    /// configured, proxies (including system proxies) are not used at all.
+    pub fn with_proxy(mut self, proxy: Url) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// sets the username and password for basic authentication at the proxy
+    pub fn with_proxy_credentials(mut self, username: String, password: String) -> Self {
+        self.proxy_credentials = Some((username, password));
+        self
+    }
+
+    /// sets a global timeout for all requests, which is applied from when the request
+    /// starts connecting until the response body has finished.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// sets a timeout which is applied to every single API request,
+    /// overriding the global timeout
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// sends all documents through the ingest pipeline with the id `pipeline`
+    pub fn with_pipeline(mut self, pipeline: String) -> Self {
+        self.pipeline = Some(pipeline);
+        self
+    }
+
+    pub fn with_id_strategy(mut self, id_strategy: IdStrategy) -> Self {
+        self.id_strategy = id_strategy;
+        self
+    }
+
+    pub fn with_bulk_mode(mut self, bulk_mode: BulkMode) -> Self {
+        self.bulk_mode = bulk_mode;
+        self
+    }
+
+    /// adds the index to the alias `alias` when it is created
+    pub fn with_alias(mut self, alias: String) -> Self {
+        self.aliases.push((alias, false));
+        self
+    }
+
+    /// adds the index to the alias `alias` when it is created, and makes it the
+    /// write index of this alias, so that documents can be imported through the alias
+    pub fn with_write_alias(mut self, alias: String) -> Self {
+        self.aliases.push((alias, true));
+        self
+    }
+
+    pub fn index_name(&self) -> &str {
+        &self.index_name
+    }
+
     pub fn host(&self) -> &str {
         match self.host.as_ref() {
             Some(h) => h,
@@ -81,117 +187,193 @@ impl IndexBuilder {
         self.client_has_index(&client).await
     }
 
-    pub async fn connect(self) -> Result<Index> {
+    pub async fn connect(&self) -> Result<Index> {
         let client = self.create_client()?;
-        Ok(Index::new(self.index_name, client))
+        let mapping_checker = if self.client_has_index(&client).await? {
+            match self.mapping().await {
+                Ok(mapping) => MappingChecker::from_mapping(&mapping),
+                Err(why) => {
+                    log::warn!("unable to check documents against the mapping: {why}");
+                    MappingChecker::default()
+                }
+            }
+        } else {
+            // the index will be created with a dynamic mapping
+            MappingChecker::default()
+        };
+        let mut index = self.create_index_object(client)?;
+        index.set_mapping_checker(Some(mapping_checker));
+        Ok(index)
     }
 
     pub async fn create_index(&self) -> Result<Index> {
         let client = self.create_client()?;
 
-        if !self.client_has_index(&client).await? {
+        let mapping = if self.client_has_index(&client).await? {
+            self.mapping().await?
+        } else {
             log::info!("create index with mappings");
-            let index_body = json!({
-                "mappings": {
+            let mut index_body = json!({"mappings": Self::default_mappings()});
+            for (alias, is_write_index) in self.aliases.iter() {
+                index_body["aliases"][alias] = json!({"is_write_index": is_write_index});
+            }
+            let parts = IndicesCreateParts::Index(&self.index_name);
+            let indices = client.indices();
+            let request = indices.create(parts).body(index_body);
+            let response = with_request_timeout!(request, self.request_timeout)
+                .send()
+                .await?;
+            check_response(response, "creating index").await?;
+            Self::default_mappings()
+        };
+        let mut index = self.create_index_object(client)?;
+        index.set_mapping_checker(Some(MappingChecker::from_mapping(&mapping)));
+        Ok(index)
+    }
+
+    /// returns the mapping which is used by [`IndexBuilder::create_index`]
+    ///
+    /// Dates are mapped as `date_nanos`, so that elasticsearch searches and sorts
+    /// them with nanosecond precision. `date_nanos` cannot store dates before 1970
+    /// or after 2262; the [`MappingChecker`] rejects documents which contain such dates.
+    pub fn default_mappings() -> Value {
+        json!({
+            "properties": {
+                "@timestamp": {
+                    "type": "date_nanos",
+                    "format": "strict_date_optional_time_nanos||epoch_millis"
+                },
+                "tags": {
+                    "type": "keyword"
+                },
+                "event": {
+                    "properties": {
+                        "ingested": {
+                            "type": "date"
+                        }
+                    }
+                },
+                "file": {
                     "properties": {
-                        "@timestamp": {
-                            "type": "date",
-                            "format": "epoch_millis"
+                        "accessed": {
+                            "type": "date_nanos",
+                            "format": "strict_date_optional_time_nanos||epoch_millis"
+                        },
+                        "created": {
+                            "type": "date_nanos",
+                            "format": "strict_date_optional_time_nanos||epoch_millis"
                         },
-                        "tags": {
+                        "ctime": {
+                            "type": "date_nanos",
+                            "format": "strict_date_optional_time_nanos||epoch_millis"
+                        },
+                        "mtime": {
+                            "type": "date_nanos",
+                            "format": "strict_date_optional_time_nanos||epoch_millis"
+                        },
+                        "macb_short": {
                             "type": "keyword"
                         },
+                        "macb_long": {
+                            "type": "keyword"
+                        }
+                    }
+                },
+                "log": {
+                    "properties": {
                         "file": {
                             "properties": {
-                                "accessed": {
-                                    "type": "date",
-                                    "format": "epoch_millis"
-                                },
-                                "created": {
-                                    "type": "date",
-                                    "format": "epoch_millis"
-                                },
-                                "ctime": {
-                                    "type": "date",
-                                    "format": "epoch_millis"
-                                },
-                                "mtime": {
-                                    "type": "date",
-                                    "format": "epoch_millis"
+                                "path": {
+                                    "type": "keyword"
+                                }
+                            }
+                        },
+                        "offset": {
+                            "type": "long"
+                        }
+                    }
+                },
+                "forensics": {
+                    "properties": {
+                        "source": {
+                            "properties": {
+                                "line": {
+                                    "type": "long"
                                 },
-                                "macb_short": {
+                                "parser": {
                                     "type": "keyword"
                                 },
-                                "macb_long": {
+                                "sha256": {
                                     "type": "keyword"
                                 }
                             }
+                        },
+                        "clock_correction": {
+                            "properties": {
+                                "offset": {
+                                    "type": "keyword"
+                                },
+                                "original": {
+                                    "properties": {
+                                        "@timestamp": {
+                                            "type": "date_nanos",
+                                            "format": "strict_date_optional_time_nanos||epoch_millis"
+                                        },
+                                        "file": {
+                                            "properties": {
+                                                "accessed": {
+                                                    "type": "date_nanos",
+                                                    "format": "strict_date_optional_time_nanos||epoch_millis"
+                                                },
+                                                "created": {
+                                                    "type": "date_nanos",
+                                                    "format": "strict_date_optional_time_nanos||epoch_millis"
+                                                },
+                                                "ctime": {
+                                                    "type": "date_nanos",
+                                                    "format": "strict_date_optional_time_nanos||epoch_millis"
+                                                },
+                                                "mtime": {
+                                                    "type": "date_nanos",
+                                                    "format": "strict_date_optional_time_nanos||epoch_millis"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
                 }
-            });
-            let parts = IndicesCreateParts::Index(&self.index_name);
-            let response = client
-                .indices()
-                .create(parts)
-                .body(index_body)
-                .send()
-                .await?;
-            match response.error_for_status_code_ref() {
-                Ok(_response) => (),
-                Err(why) => {
-                    log::error!(
-                        "Error while creating index: {}",
-                        response.text().await?
-                    );
-                    log::error!("error message was: {}", why);
-                    return Err(anyhow!(why))
-                }
             }
+        })
+    }
 
-            //let pipeline_id = format!("{}_pipeline", self.index_name());
-            //self.create_pipeline(&client, &pipeline_id).await?;
-        }
-        Ok(Index::new(self.index_name.clone(), client))
-    }
-/*
-    async fn create_pipeline(&self, client: &Elasticsearch, pipeline_id: &str) -> Result<()> {
-        let pipeline_parts = IngestPutPipelineParts::Id(pipeline_id);
-        let set_timestamp = json!({
-            "description": "Creates a timestamp when a document is initially indexed",
-            "processors": [
-                {
-                    "set": {
-                        "field": "timestamp",
-                        "value": "{{{_ingest.timestamp}}}"
-                    }
-                }
-            ]
-        });
-        let ingest_response = client
-            .ingest()
+    fn create_index_object(&self, client: Elasticsearch) -> Result<Index> {
+        let mut index = Index::new(self.index_name.clone(), client);
+        index.set_request_timeout(self.request_timeout);
+        index.set_pipeline(self.pipeline.clone());
+        index.set_id_strategy_and_bulk_mode(self.id_strategy.clone(), self.bulk_mode.clone())?;
+        Ok(index)
+    }
+
+    /// creates the ingest pipeline, or replaces it if a pipeline with the same id exists already
+    pub async fn put_pipeline(&self, pipeline: &Pipeline) -> Result<()> {
+        let client = self.create_client()?;
+        let pipeline_parts = IngestPutPipelineParts::Id(pipeline.id());
+        let ingest = client.ingest();
+        let request = ingest
             .put_pipeline(pipeline_parts)
-            .body(set_timestamp)
+            .body(pipeline.body().clone());
+        let ingest_response = with_request_timeout!(request, self.request_timeout)
             .send()
             .await?;
-
-        match ingest_response.error_for_status_code_ref() {
-            Err(why) => {
-                log::error!(
-                    "Error while creating pipeline: {}",
-                    ingest_response.text().await?
-                );
-                log::error!("error message was: {}", why);
-                Err(anyhow!(why))
-            }
-            Ok(_response) => {
-                log::info!("sucessfully created pipeline {pipeline_id}");
-                Ok(())
-            }
-        }
+        check_response(ingest_response, "creating pipeline").await?;
+        log::info!("sucessfully created pipeline {}", pipeline.id());
+        Ok(())
     }
-*/
-    fn create_client(&self) -> Result<Elasticsearch> {
+
+    pub(crate) fn create_client(&self) -> Result<Elasticsearch> {
         let url = Url::parse(&format!("{}://{}:{}", self.protocol, self.host(), self.port()))?;
         let conn_pool = SingleNodeConnectionPool::new(url);
         let mut transport_builder = TransportBuilder::new(conn_pool)
@@ -199,8 +381,22 @@ impl IndexBuilder {
                 CertificateValidation::Default
             } else {
                 CertificateValidation::None
-            })
-            .disable_proxy();
+            });
+
+        transport_builder = match &self.proxy {
+            None => transport_builder.disable_proxy(),
+            Some(proxy) => {
+                let (username, password) = match &self.proxy_credentials {
+                    Some((u, p)) => (Some(u.as_str()), Some(p.as_str())),
+                    None => (None, None),
+                };
+                transport_builder.proxy(proxy.clone(), username, password)
+            }
+        };
+
+        if let Some(timeout) = self.timeout {
+            transport_builder = transport_builder.timeout(timeout);
+        }
 
         if let Some(credentials) = &self.credentials {
             transport_builder = transport_builder.auth(credentials.clone());
@@ -212,31 +408,25 @@ impl IndexBuilder {
     async fn client_has_index(&self, client: &Elasticsearch) -> Result<bool> {
         log::info!("test if index '{}' exists", self.index_name);
 
-        let response = client
-            .cat()
-            .indices(CatIndicesParts::Index(&["*"]))
-            .format("json")
+        let index_names = [self.index_name.as_str()];
+        let indices = client.indices();
+        let request = indices.exists(IndicesExistsParts::Index(&index_names));
+        let response = with_request_timeout!(request, self.request_timeout)
             .send()
             .await?;
-        response.error_for_status_code_ref()?;
-
-        if response.content_length().unwrap_or(0) == 0 {
-            log::debug!("empty result; index does not seem to exist");
-            Ok(false)
-        } else {
-            let response_body = response.json::<Value>().await?;
-
-            match response_body.as_array() {
-                None => {
-                    log::debug!("index does not exist");
-                    Ok(false)
-                }
-                Some(body) => Ok(body
-                    .iter()
-                    .any(|r| *r["index"].as_str().unwrap() == self.index_name)),
+        match response.status_code().as_u16() {
+            200 => Ok(true),
+            404 => Ok(false),
+            _ => {
+                check_response(response, "testing if the index exists").await?;
+                Ok(false)
             }
         }
     }
+
+    pub(crate) fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
 }
 
 impl WithHost<String> for IndexBuilder {
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use elasticsearch::{
+    cat::CatIndicesParts,
+    indices::{IndicesDeleteParts, IndicesGetMappingParts},
+    SearchParts,
+};
+use serde_json::{json, Value};
+
+use crate::index_builder::{check_response, with_request_timeout, IndexBuilder};
+
+/// Summary of an index, as displayed by `es4forensics list-indices`
+pub struct IndexInfo {
+    pub name: String,
+    pub status: String,
+    pub docs_count: u64,
+    pub store_size: String,
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+}
+
+/// the oldest and the newest `@timestamp` of an index
+type TimeRange = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+impl IndexBuilder {
+    /// returns information about all indices whose name matches the index
+    /// name of this builder, which may contain wildcards
+    pub async fn list_indices(&self) -> Result<Vec<IndexInfo>> {
+        let client = self.create_client()?;
+        let index_names = [self.index_name()];
+        let cat = client.cat();
+        let request = cat
+            .indices(CatIndicesParts::Index(&index_names))
+            .h(&["index", "status", "docs.count", "store.size"])
+            .format("json");
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "listing indices").await?;
+        let body: Value = response.json().await?;
+
+        let entries = body.as_array().map(Vec::as_slice).unwrap_or_default();
+        let open_indices: Vec<&str> = entries
+            .iter()
+            .filter(|entry| entry["status"] == "open")
+            .filter_map(|entry| entry["index"].as_str())
+            .collect();
+        let mut time_ranges = self.time_ranges(&open_indices).await?;
+
+        let mut result = Vec::new();
+        for entry in entries {
+            let name = entry["index"].as_str().unwrap_or_default().to_owned();
+            let (first_timestamp, last_timestamp) = time_ranges.remove(&name).unwrap_or_default();
+            result.push(IndexInfo {
+                docs_count: entry["docs.count"]
+                    .as_str()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or_default(),
+                store_size: entry["store.size"].as_str().unwrap_or_default().to_owned(),
+                status: entry["status"].as_str().unwrap_or_default().to_owned(),
+                name,
+                first_timestamp,
+                last_timestamp,
+            });
+        }
+        result.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(result)
+    }
+
+    /// returns the oldest and the newest value of `@timestamp` of every index which
+    /// contains documents, using a single search request. Closed indices cannot be searched.
+    async fn time_ranges(&self, index_names: &[&str]) -> Result<HashMap<String, TimeRange>> {
+        if index_names.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let client = self.create_client()?;
+        let request = client.search(SearchParts::Index(index_names)).body(json!({
+            "size": 0,
+            "aggs": {
+                "indices": {
+                    "terms": { "field": "_index", "size": index_names.len() },
+                    "aggs": {
+                        "first": { "min": { "field": "@timestamp" } },
+                        "last": { "max": { "field": "@timestamp" } }
+                    }
+                }
+            }
+        }));
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "querying the time ranges").await?;
+        let body: Value = response.json().await?;
+        let to_datetime = |agg: &Value| {
+            agg["value"]
+                .as_f64()
+                .and_then(|v| DateTime::from_timestamp_millis(v as i64))
+        };
+        Ok(body["aggregations"]["indices"]["buckets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|bucket| {
+                let name = bucket["key"].as_str()?.to_owned();
+                Some((name, (to_datetime(&bucket["first"]), to_datetime(&bucket["last"]))))
+            })
+            .collect())
+    }
+
+    /// returns the mapping of the index
+    pub async fn mapping(&self) -> Result<Value> {
+        let client = self.create_client()?;
+        let index_names = [self.index_name()];
+        let indices = client.indices();
+        let request = indices.get_mapping(IndicesGetMappingParts::Index(&index_names));
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "reading the mapping").await?;
+        let mut body: Value = response.json().await?;
+        if body.get(self.index_name()).is_none() {
+            // if the index name is an alias, the mapping is reported for the concrete index
+            if let Some(mut indices) = body.as_object_mut().map(std::mem::take) {
+                if indices.len() == 1 {
+                    return Ok(indices.values_mut().next().unwrap()["mappings"].take());
+                }
+            }
+        }
+        match body.get_mut(self.index_name()) {
+            Some(mapping) => Ok(mapping["mappings"].take()),
+            None => Err(anyhow!("no unique mapping found for '{}'", self.index_name())),
+        }
+    }
+
+    /// returns an arbitrary document from the index, or `None` if the index is empty
+    pub async fn sample_document(&self) -> Result<Option<Value>> {
+        let client = self.create_client()?;
+        let index_names = [self.index_name()];
+        let request = client
+            .search(SearchParts::Index(&index_names))
+            .body(json!({"size": 1}));
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "reading a sample document").await?;
+        let mut body: Value = response.json().await?;
+        Ok(body["hits"]["hits"]
+            .as_array_mut()
+            .and_then(|hits| hits.pop())
+            .map(|mut hit| hit["_source"].take()))
+    }
+
+    pub async fn delete_index(&self) -> Result<()> {
+        let client = self.create_client()?;
+        let index_names = [self.index_name()];
+        let indices = client.indices();
+        let request = indices.delete(IndicesDeleteParts::Index(&index_names));
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        check_response(response, "deleting the index").await?;
+        log::info!("deleted index '{}'", self.index_name());
+        Ok(())
+    }
+}
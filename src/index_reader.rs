@@ -0,0 +1,143 @@
+use anyhow::Result;
+use elasticsearch::{OpenPointInTimeParts, SearchParts};
+use serde_json::{json, Value};
+
+use crate::{
+    index_builder::{check_response, with_request_timeout, IndexBuilder},
+    TimelineQuery,
+};
+
+const KEEP_ALIVE: &str = "5m";
+
+/// Reads all documents which match a [`TimelineQuery`] from an index, sorted by `@timestamp`.
+///
+/// The documents are read page by page, using a point in time (which keeps
+/// the view on the index consistent) and `search_after`.
+pub struct IndexReader<'b> {
+    builder: &'b IndexBuilder,
+    client: elasticsearch::Elasticsearch,
+    query: Value,
+    page_size: usize,
+    pit_id: Option<String>,
+    search_after: Option<Value>,
+    finished: bool,
+}
+
+impl IndexBuilder {
+    pub async fn open_reader(&self, query: &TimelineQuery) -> Result<IndexReader<'_>> {
+        let client = self.create_client()?;
+        let index_names = [self.index_name()];
+        let request = client
+            .open_point_in_time(OpenPointInTimeParts::Index(&index_names))
+            .keep_alive(KEEP_ALIVE);
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "opening point in time").await?;
+        let body: Value = response.json().await?;
+
+        Ok(IndexReader {
+            builder: self,
+            client,
+            query: query.to_query(),
+            page_size: 1000,
+            pit_id: body["id"].as_str().map(|s| s.to_owned()),
+            search_after: None,
+            finished: false,
+        })
+    }
+}
+
+impl IndexBuilder {
+    /// returns the `_source` of the first `size` documents which match `query`, sorted by `@timestamp`
+    pub async fn search(&self, query: &TimelineQuery, size: usize) -> Result<Vec<Value>> {
+        let client = self.create_client()?;
+        let index_names = [self.index_name()];
+        let request = client.search(SearchParts::Index(&index_names)).body(json!({
+            "size": size,
+            "query": query.to_query(),
+            "sort": [{"@timestamp": "asc"}]
+        }));
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "searching documents").await?;
+        let mut body: Value = response.json().await?;
+        Ok(body["hits"]["hits"]
+            .as_array_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|mut hit| hit["_source"].take())
+            .collect())
+    }
+}
+
+impl<'b> IndexReader<'b> {
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// returns the `_source` of the next documents, or `None` if all documents have been read
+    pub async fn next_page(&mut self) -> Result<Option<Vec<Value>>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut body = json!({
+            "size": self.page_size,
+            "query": self.query,
+            "pit": {"id": self.pit_id, "keep_alive": KEEP_ALIVE},
+            "sort": [{"@timestamp": "asc"}, {"_shard_doc": "asc"}]
+        });
+        if let Some(search_after) = &self.search_after {
+            body["search_after"] = search_after.clone();
+        }
+
+        let request = self.client.search(SearchParts::None).body(body);
+        let response = with_request_timeout!(request, self.builder.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "reading documents").await?;
+        let mut body: Value = response.json().await?;
+
+        // the point in time id might change with every request
+        if let Some(pit_id) = body["pit_id"].as_str() {
+            self.pit_id = Some(pit_id.to_owned());
+        }
+
+        let hits = match body["hits"]["hits"].as_array_mut() {
+            Some(hits) if !hits.is_empty() => std::mem::take(hits),
+            _ => {
+                self.finished = true;
+                return Ok(None);
+            }
+        };
+        if hits.len() < self.page_size {
+            self.finished = true;
+        }
+        self.search_after = hits.last().map(|hit| hit["sort"].clone());
+
+        Ok(Some(
+            hits.into_iter()
+                .map(|mut hit| hit["_source"].take())
+                .collect(),
+        ))
+    }
+
+    /// releases the point in time
+    pub async fn close(self) -> Result<()> {
+        if let Some(pit_id) = self.pit_id {
+            let request = self
+                .client
+                .close_point_in_time()
+                .body(json!({ "id": pit_id }));
+            let response = with_request_timeout!(request, self.builder.request_timeout())
+                .send()
+                .await?;
+            check_response(response, "closing point in time").await?;
+        }
+        Ok(())
+    }
+}
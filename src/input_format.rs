@@ -0,0 +1,258 @@
+use anyhow::{bail, Result};
+use bodyfile::Bodyfile3Line;
+use chrono_tz::Tz;
+use csv::StringRecord;
+use serde_json::Value;
+
+use crate::{
+    objects::{L2tCsvEvent, MactimeEvent, PlasoEvent, PlasoMapping, PosixFile, L2TCSV_HEADER, MACTIME_HEADER},
+    timeline_filter::is_unset,
+    ClockCorrection, DstPolicy, EcsBuilder, TimelineFilter, TimelineObject, TimestampFormat,
+};
+
+/// format of timelines which can be imported or converted
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum InputFormat {
+    /// one ECS document per line
+    #[default]
+    Json,
+
+    /// bodyfile lines, as created by fls
+    Bodyfile,
+
+    /// CSV output of plaso (`psort.py -o l2tcsv`)
+    L2tcsv,
+
+    /// JSON lines output of plaso (`psort.py -o json_line`)
+    JsonLine,
+
+    /// delimited output of mactime (`mactime -d`)
+    Mactime,
+}
+
+impl InputFormat {
+    /// name of the parser, as stored in `forensics.source.parser`
+    pub fn parser_name(&self) -> &'static str {
+        match self {
+            InputFormat::Json => "json",
+            InputFormat::Bodyfile => "bodyfile",
+            InputFormat::L2tcsv => "l2tcsv",
+            InputFormat::JsonLine => "json_line",
+            InputFormat::Mactime => "mactime",
+        }
+    }
+
+    /// returns `true` if timestamps of this format are interpreted using the
+    /// timezone of the [`TimelineParser`]
+    pub fn uses_timezone(&self) -> bool {
+        matches!(self, InputFormat::Bodyfile | InputFormat::Mactime)
+    }
+
+    /// returns `true` if the input consists of CSV records, which must be read with
+    /// a CSV reader because quoted fields may contain line breaks
+    pub fn is_csv(&self) -> bool {
+        matches!(self, InputFormat::L2tcsv)
+    }
+}
+
+/// Converts the lines of a timeline into ECS documents
+pub struct TimelineParser {
+    format: InputFormat,
+    timezone: Tz,
+    dst_policy: DstPolicy,
+    plaso_mapping: PlasoMapping,
+    clock_correction: Option<ClockCorrection>,
+    filter: Option<TimelineFilter>,
+    timestamp_format: TimestampFormat,
+
+    /// number of documents which have not been selected by the filter
+    filtered_documents: u64,
+}
+
+impl TimelineParser {
+    pub fn new(format: InputFormat) -> Self {
+        Self {
+            format,
+            timezone: Tz::UTC,
+            dst_policy: DstPolicy::default(),
+            plaso_mapping: PlasoMapping::default(),
+            clock_correction: None,
+            filter: None,
+            timestamp_format: TimestampFormat::default(),
+            filtered_documents: 0,
+        }
+    }
+
+    /// sets the timezone which is used for formats which store local timestamps
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// sets how local timestamps are resolved which are affected by DST transitions
+    pub fn with_dst_policy(mut self, dst_policy: DstPolicy) -> Self {
+        self.dst_policy = dst_policy;
+        self
+    }
+
+    /// sets the mapping of plaso attributes to ECS fields, which is used for `json_line` input
+    pub fn with_plaso_mapping(mut self, plaso_mapping: PlasoMapping) -> Self {
+        self.plaso_mapping = plaso_mapping;
+        self
+    }
+
+    /// corrects the timestamps of all generated documents by the offset of the host's clock
+    pub fn with_clock_correction(mut self, clock_correction: ClockCorrection) -> Self {
+        self.clock_correction = Some(clock_correction);
+        self
+    }
+
+    /// drops all generated documents which are not selected by `filter`. The
+    /// filter is applied after the clock correction, but before the documents
+    /// are serialized (except for `json` input, which is filtered by its values).
+    pub fn with_filter(mut self, filter: TimelineFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// sets the format of the timestamps in generated documents. Documents of `json`
+    /// input are kept as they are.
+    pub fn with_timestamp_format(mut self, timestamp_format: TimestampFormat) -> Self {
+        self.timestamp_format = timestamp_format;
+        self
+    }
+
+    /// returns the number of documents which have been dropped by the filter so far
+    pub fn filtered_documents(&self) -> u64 {
+        self.filtered_documents
+    }
+
+    /// changes the format of the input, keeping all other settings
+    pub fn with_format(mut self, format: InputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn format(&self) -> &InputFormat {
+        &self.format
+    }
+
+    /// converts one line of the input into documents. Header lines yield no documents.
+    pub fn parse_line(&mut self, line: &str) -> Result<Vec<Value>> {
+        let (values, filtered) = self.parse_record(line)?;
+        self.filtered_documents += filtered;
+        Ok(self.finish(values))
+    }
+
+    /// converts one record of CSV input into documents, see [`InputFormat::is_csv`].
+    /// Header records yield no documents.
+    pub fn parse_csv_record(&mut self, record: &StringRecord) -> Result<Vec<Value>> {
+        let (values, filtered) = match self.format {
+            InputFormat::L2tcsv => {
+                if record.iter().take(4).eq(L2TCSV_HEADER[..4].iter().copied()) {
+                    return Ok(Vec::new());
+                }
+                self.values(L2tCsvEvent::try_from((record.clone(), self.dst_policy))?)?
+            }
+            _ => bail!("{} input does not consist of CSV records", self.format.parser_name()),
+        };
+        self.filtered_documents += filtered;
+        Ok(self.finish(values))
+    }
+
+    /// corrects the clock of the selected documents and writes their timestamps in
+    /// the configured format. Documents of `json` input are filtered here.
+    fn finish(&mut self, mut values: Vec<Value>) -> Vec<Value> {
+        if let Some(clock_correction) = &self.clock_correction {
+            values.iter_mut().for_each(|v| clock_correction.apply(v));
+        }
+        if matches!(self.format, InputFormat::Json) {
+            if let Some(filter) = &self.filter {
+                let count = values.len();
+                values.retain(|v| filter.matches(v));
+                self.filtered_documents += (count - values.len()) as u64;
+            }
+        } else {
+            values.iter_mut().for_each(|v| self.timestamp_format.apply(v));
+        }
+        values
+    }
+
+    /// returns the selected documents of a line and the number of documents which
+    /// have not been selected
+    fn parse_record(&self, line: &str) -> Result<(Vec<Value>, u64)> {
+        match self.format {
+            InputFormat::Json => Ok((vec![serde_json::from_str(line)?], 0)),
+            InputFormat::Bodyfile => {
+                let bfline = Bodyfile3Line::try_from(line)?;
+                let file = PosixFile::try_from((&bfline, &self.timezone, self.dst_policy))?;
+                let times = [bfline.get_atime(), bfline.get_mtime(), bfline.get_ctime(), bfline.get_crtime()];
+                self.selected_values(file, times.into_iter().all(is_unset))
+            }
+            InputFormat::L2tcsv => {
+                if line.starts_with(&L2TCSV_HEADER[..4].join(",")) {
+                    return Ok((Vec::new(), 0));
+                }
+                self.values(L2tCsvEvent::try_from((line, self.dst_policy))?)
+            }
+            InputFormat::JsonLine => {
+                let event = serde_json::from_str(line)?;
+                self.values(PlasoEvent::new(event, &self.plaso_mapping)?)
+            }
+            InputFormat::Mactime => {
+                if line.starts_with(&MACTIME_HEADER[..3].join(",")) {
+                    return Ok((Vec::new(), 0));
+                }
+                let event = MactimeEvent::try_from((line, &self.timezone, self.dst_policy))?;
+                // mactime has written the time in our timezone, so its value is the UTC time
+                let millis = event.timestamp().timestamp_millis();
+                let all_unset = millis % 1000 == 0 && is_unset(millis / 1000);
+                self.selected_values(event, all_unset)
+            }
+        }
+    }
+
+    fn values<T: TimelineObject>(&self, object: T) -> Result<(Vec<Value>, u64)> {
+        self.selected_values(object, false)
+    }
+
+    /// serializes the documents of `object` which are selected by the filter, and
+    /// counts the others. If the timestamps of the input line are `all_unset`, no
+    /// documents are selected if the filter skips empty timestamps.
+    fn selected_values<T: TimelineObject>(&self, object: T, all_unset: bool) -> Result<(Vec<Value>, u64)> {
+        let skip_all = all_unset && self.filter.as_ref().is_some_and(|f| f.skips_empty_timestamps());
+        let mut values = Vec::new();
+        let mut filtered = 0;
+        for builder in object {
+            let builder = builder?;
+            if skip_all || !self.selects(&builder) {
+                filtered += 1;
+                continue;
+            }
+            let (_, value) = builder.into();
+            values.push(value);
+        }
+        Ok((values, filtered))
+    }
+
+    /// applies the filter to the timestamp (after the clock correction) and the path
+    /// of a document which has not been serialized yet
+    fn selects(&self, builder: &EcsBuilder) -> bool {
+        let filter = match &self.filter {
+            Some(filter) => filter,
+            None => return true,
+        };
+        let timestamp = || {
+            let offset = self
+                .clock_correction
+                .as_ref()
+                .and_then(|c| c.offset_of_fields(|field| builder.field(field)));
+            Some(match offset {
+                Some(offset) => ClockCorrection::corrected_timestamp(builder.timestamp(), offset),
+                None => builder.timestamp().clone(),
+            })
+        };
+        filter.matches_fields(timestamp, builder.field("file.path").and_then(|p| p.as_str()))
+    }
+}
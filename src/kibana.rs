@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::{Client, Proxy, Url};
+use serde_json::{json, Value};
+
+/// columns of the saved search which is created by [`Kibana::create_saved_search`]
+pub const DEFAULT_COLUMNS: [&str; 5] = ["message", "host.name", "event.code", "file.path", "tags"];
+
+/// Client for the saved objects API of Kibana, which creates data views and
+/// saved searches for case indices.
+///
+/// All saved objects get ids which are derived from the index name, so that
+/// provisioning an index a second time updates the existing objects.
+pub struct Kibana {
+    url: Url,
+    space: Option<String>,
+    credentials: Option<(String, String)>,
+    do_certificate_validation: bool,
+    proxy: Option<Url>,
+    proxy_credentials: Option<(String, String)>,
+    timeout: Option<Duration>,
+}
+
+impl Kibana {
+    pub fn with_url(mut url: Url) -> Self {
+        // otherwise, the last path segment would be replaced when joining URLs
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        Self {
+            url,
+            space: None,
+            credentials: None,
+            do_certificate_validation: true,
+            proxy: None,
+            proxy_credentials: None,
+            timeout: None,
+        }
+    }
+
+    /// creates the saved objects in a Kibana space instead of the default space
+    pub fn with_space(mut self, space: String) -> Self {
+        self.space = Some(space);
+        self
+    }
+
+    pub fn with_credentials(mut self, username: String, password: String) -> Self {
+        self.credentials = Some((username, password));
+        self
+    }
+
+    pub fn without_certificate_validation(mut self) -> Self {
+        self.do_certificate_validation = false;
+        self
+    }
+
+    pub fn with_proxy(mut self, proxy: Url) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn with_proxy_credentials(mut self, username: String, password: String) -> Self {
+        self.proxy_credentials = Some((username, password));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// creates a data view for `index_pattern`, which uses `@timestamp` as time field,
+    /// and returns the id of the data view
+    pub async fn create_data_view(&self, index_pattern: &str) -> Result<String> {
+        let id = format!("es4forensics-{index_pattern}");
+        let body = json!({
+            "attributes": {
+                "title": index_pattern,
+                "timeFieldName": "@timestamp"
+            }
+        });
+        self.put_saved_object("index-pattern", &id, body).await?;
+        log::info!("created data view '{id}'");
+        Ok(id)
+    }
+
+    /// creates a saved search for the data view `data_view_id`, which displays `columns`
+    /// and sorts by `@timestamp`, and returns the id of the saved search
+    pub async fn create_saved_search(
+        &self,
+        data_view_id: &str,
+        title: &str,
+        columns: &[&str],
+    ) -> Result<String> {
+        let id = format!("{data_view_id}-search");
+        let search_source = json!({
+            "query": {"query": "", "language": "kuery"},
+            "filter": [],
+            "indexRefName": "kibanaSavedObjectMeta.searchSourceJSON.index"
+        });
+        let body = json!({
+            "attributes": {
+                "title": title,
+                "columns": columns,
+                "sort": [["@timestamp", "asc"]],
+                "kibanaSavedObjectMeta": {
+                    "searchSourceJSON": search_source.to_string()
+                }
+            },
+            "references": [{
+                "name": "kibanaSavedObjectMeta.searchSourceJSON.index",
+                "type": "index-pattern",
+                "id": data_view_id
+            }]
+        });
+        self.put_saved_object("search", &id, body).await?;
+        log::info!("created saved search '{id}'");
+        Ok(id)
+    }
+
+    async fn put_saved_object(&self, object_type: &str, id: &str, body: Value) -> Result<Value> {
+        let path = match &self.space {
+            Some(space) => format!("s/{space}/api/saved_objects/{object_type}/{id}"),
+            None => format!("api/saved_objects/{object_type}/{id}"),
+        };
+        let url = self.url.join(&path)?;
+
+        let mut request = self
+            .create_client()?
+            .post(url)
+            .query(&[("overwrite", "true")])
+            .header("kbn-xsrf", "true")
+            .json(&body);
+        if let Some((username, password)) = &self.credentials {
+            request = request.basic_auth(username, Some(password));
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(response.json().await?)
+        } else {
+            log::error!("Error while creating {object_type}: {}", response.text().await?);
+            Err(anyhow!("kibana returned status {status} while creating {object_type} '{id}'"))
+        }
+    }
+
+    fn create_client(&self) -> Result<Client> {
+        let mut builder =
+            Client::builder().danger_accept_invalid_certs(!self.do_certificate_validation);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder = match &self.proxy {
+            None => builder.no_proxy(),
+            Some(url) => {
+                let mut proxy = Proxy::all(url.clone())?;
+                if let Some((username, password)) = &self.proxy_credentials {
+                    proxy = proxy.basic_auth(username, password);
+                }
+                builder.proxy(proxy)
+            }
+        };
+        Ok(builder.build()?)
+    }
+}
@@ -72,9 +72,37 @@ mod index;
 #[cfg(feature="elasticsearch")]
 mod index_builder;
 
+#[cfg(feature="elasticsearch")]
+mod index_management;
+
+#[cfg(feature="elasticsearch")]
+mod index_reader;
+
+#[cfg(feature="elasticsearch")]
+mod alias;
+
+#[cfg(feature="elasticsearch")]
+mod lifecycle;
+
+#[cfg(feature="kibana")]
+mod kibana;
+
 mod timestamp;
+mod dst_policy;
 mod utils;
 mod ecs;
+mod pipeline;
+mod document_id;
+mod timeline_query;
+mod timeline_writer;
+mod mapping_checker;
+mod import_statistics;
+mod import_audit;
+mod provenance;
+mod input_format;
+mod clock_correction;
+mod timeline_filter;
+mod timeline_sorter;
 
 #[cfg(feature="cli")]
 mod protocol;
@@ -84,7 +112,28 @@ pub use index::*;
 
 #[cfg(feature="elasticsearch")]
 pub use index_builder::*;
+
+#[cfg(feature="elasticsearch")]
+pub use index_management::*;
+
+#[cfg(feature="elasticsearch")]
+pub use index_reader::*;
+#[cfg(feature="kibana")]
+pub use kibana::*;
 pub use timestamp::*;
+pub use dst_policy::*;
 pub use ecs::*;
+pub use pipeline::*;
+pub use document_id::*;
+pub use timeline_query::*;
+pub use timeline_writer::*;
+pub use mapping_checker::*;
+pub use import_statistics::*;
+pub use import_audit::*;
+pub use provenance::*;
+pub use input_format::*;
+pub use clock_correction::*;
+pub use timeline_filter::*;
+pub use timeline_sorter::*;
 #[cfg(feature="cli")]
 pub use protocol::*;
\ No newline at end of file
@@ -0,0 +1,100 @@
+use anyhow::{bail, Result};
+use elasticsearch::{
+    indices::IndicesPutSettingsParts,
+    snapshot::{SnapshotCreateParts, SnapshotRestoreParts},
+};
+use serde_json::{json, Value};
+
+use crate::index_builder::{check_response, with_request_timeout, IndexBuilder};
+
+impl IndexBuilder {
+    /// attaches the index lifecycle management policy `policy` to the index,
+    /// e.g. to move the index of a closed case into the warm or cold tier
+    pub async fn set_lifecycle_policy(&self, policy: &str) -> Result<()> {
+        let client = self.create_client()?;
+        let index_names = [self.index_name()];
+        let indices = client.indices();
+        let request = indices
+            .put_settings(IndicesPutSettingsParts::Index(&index_names))
+            .body(json!({"index.lifecycle.name": policy}));
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        check_response(response, "setting the lifecycle policy").await?;
+        log::info!("attached lifecycle policy '{policy}' to '{}'", self.index_name());
+        Ok(())
+    }
+
+    /// creates a snapshot of the index (or of all indices of an alias) in the
+    /// registered snapshot repository `repository` and waits until it has finished
+    pub async fn create_snapshot(&self, repository: &str, snapshot: &str) -> Result<()> {
+        let client = self.create_client()?;
+        let snapshot_api = client.snapshot();
+        let request = snapshot_api
+            .create(SnapshotCreateParts::RepositorySnapshot(repository, snapshot))
+            .wait_for_completion(true)
+            .body(json!({
+                "indices": self.index_name(),
+                "include_global_state": false,
+                "metadata": {
+                    "taken_by": "es4forensics",
+                    "index": self.index_name()
+                }
+            }));
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "creating the snapshot").await?;
+        let body: Value = response.json().await?;
+        Self::check_shard_failures(&body["snapshot"]["shards"])?;
+        log::info!("created snapshot '{snapshot}' of '{}' in '{repository}'", self.index_name());
+        Ok(())
+    }
+
+    /// restores all indices which match the index name of this builder from the
+    /// snapshot `snapshot`. If `rename_to` is given, the restored index gets this
+    /// name instead of its original name; `$1` will be replaced by the original name.
+    pub async fn restore_snapshot(
+        &self,
+        repository: &str,
+        snapshot: &str,
+        rename_to: Option<&str>,
+    ) -> Result<()> {
+        let mut restore_body = json!({
+            "indices": self.index_name(),
+            "include_global_state": false
+        });
+        if let Some(rename_to) = rename_to {
+            restore_body["rename_pattern"] = json!("(.+)");
+            restore_body["rename_replacement"] = json!(rename_to);
+
+            // the aliases would otherwise point to the original and to the restored index
+            restore_body["include_aliases"] = json!(false);
+        }
+
+        let client = self.create_client()?;
+        let snapshot_api = client.snapshot();
+        let request = snapshot_api
+            .restore(SnapshotRestoreParts::RepositorySnapshot(repository, snapshot))
+            .wait_for_completion(true)
+            .body(restore_body);
+        let response = with_request_timeout!(request, self.request_timeout())
+            .send()
+            .await?;
+        let response = check_response(response, "restoring the snapshot").await?;
+        let body: Value = response.json().await?;
+        Self::check_shard_failures(&body["snapshot"]["shards"])?;
+        log::info!("restored snapshot '{snapshot}' from '{repository}'");
+        Ok(())
+    }
+
+    fn check_shard_failures(shards: &Value) -> Result<()> {
+        match shards["failed"].as_u64() {
+            Some(failed) if failed > 0 => bail!(
+                "{failed} of {} shards failed",
+                shards["total"].as_u64().unwrap_or_default()
+            ),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::{collections::HashMap, fmt::Display, net::IpAddr};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde_json::Value;
+
+use crate::Timestamp;
+
+/// A value in a document which elasticsearch would reject, because it does
+/// not match the type the field is mapped to
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldConflict {
+    pub field: String,
+    pub mapped_type: String,
+    pub value: Value,
+}
+
+impl Display for FieldConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "field '{}' is mapped as '{}', but the document contains {}",
+            self.field, self.mapped_type, self.value
+        )
+    }
+}
+
+struct FieldType {
+    name: String,
+    format: Option<String>,
+}
+
+/// Checks the leaf values of documents against the mapping of an index.
+///
+/// Fields which are not part of the mapping are added the way elasticsearch
+/// would map them dynamically, so that conflicts between documents are
+/// detected as well.
+///
+/// ```
+/// use es4forensics::MappingChecker;
+/// use serde_json::json;
+///
+/// let mut checker = MappingChecker::from_mapping(&json!({
+///     "properties": {"event": {"properties": {"code": {"type": "long"}}}}
+/// }));
+/// assert!(checker.check(&json!({"event": {"code": 4624}})).is_empty());
+/// assert_eq!(checker.check(&json!({"event": {"code": "4624-a"}})).len(), 1);
+/// ```
+#[derive(Default)]
+pub struct MappingChecker {
+    fields: HashMap<String, FieldType>,
+}
+
+impl MappingChecker {
+    /// creates a checker from the `mappings` part of an index definition
+    pub fn from_mapping(mapping: &Value) -> Self {
+        let mut checker = Self::default();
+        checker.add_properties("", mapping);
+        checker
+    }
+
+    fn add_properties(&mut self, prefix: &str, mapping: &Value) {
+        if let Some(properties) = mapping["properties"].as_object() {
+            for (name, field) in properties {
+                let path = format!("{prefix}{name}");
+                let type_name = field["type"].as_str().unwrap_or("object");
+                self.fields.insert(
+                    path.clone(),
+                    FieldType {
+                        name: type_name.to_owned(),
+                        format: field["format"].as_str().map(|f| f.to_owned()),
+                    },
+                );
+                self.add_properties(&format!("{path}."), field);
+            }
+        }
+    }
+
+    /// returns the type which `field` is mapped to, if it is known
+    pub fn field_type(&self, field: &str) -> Option<&str> {
+        self.fields.get(field).map(|t| t.name.as_str())
+    }
+
+    /// returns all values of `document` which conflict with the mapping
+    pub fn check(&mut self, document: &Value) -> Vec<FieldConflict> {
+        let mut conflicts = Vec::new();
+        if let Value::Object(fields) = document {
+            for (name, value) in fields {
+                self.check_field(name, value, &mut conflicts);
+            }
+        }
+        conflicts
+    }
+
+    /// converts values which elasticsearch would store inconsistently into the
+    /// mapped type, where this is safe (e.g. numbers into strings for keyword fields),
+    /// and returns all remaining conflicts
+    pub fn coerce(&mut self, document: &mut Value) -> Vec<FieldConflict> {
+        if let Value::Object(fields) = document {
+            for (name, value) in fields.iter_mut() {
+                self.coerce_field(name, value);
+            }
+        }
+        self.check(document)
+    }
+
+    fn coerce_field(&self, path: &str, value: &mut Value) {
+        match value {
+            Value::Array(values) => {
+                for v in values.iter_mut() {
+                    self.coerce_field(path, v);
+                }
+            }
+            Value::Object(children) => {
+                for (name, child) in children.iter_mut() {
+                    self.coerce_field(&format!("{path}.{name}"), child);
+                }
+            }
+            Value::Number(_) | Value::Bool(_) => {
+                if self.fields.get(path).is_some_and(|t| t.is_keyword()) {
+                    *value = Value::String(value.to_string());
+                }
+            }
+            Value::Null | Value::String(_) => (),
+        }
+    }
+
+    fn check_field(&mut self, path: &str, value: &Value, conflicts: &mut Vec<FieldConflict>) {
+        if let Value::Array(values) = value {
+            for v in values {
+                self.check_field(path, v, conflicts);
+            }
+            return;
+        }
+        if value.is_null() {
+            return;
+        }
+
+        match self.fields.get(path) {
+            None => self.add_dynamic_field(path, value),
+            Some(field_type) => {
+                if !field_type.accepts(value) {
+                    conflicts.push(FieldConflict {
+                        field: path.to_owned(),
+                        mapped_type: field_type.name.clone(),
+                        value: value.clone(),
+                    });
+                    return;
+                }
+            }
+        }
+
+        if let Value::Object(children) = value {
+            if self.fields.get(path).is_some_and(|t| t.has_properties()) {
+                for (name, child) in children {
+                    self.check_field(&format!("{path}.{name}"), child, conflicts);
+                }
+            }
+        }
+    }
+
+    /// maps a previously unknown field like elasticsearch's dynamic mapping does
+    fn add_dynamic_field(&mut self, path: &str, value: &Value) {
+        let type_name = match value {
+            Value::Object(_) => "object",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_f64() => "float",
+            Value::Number(_) => "long",
+            Value::String(s) if looks_like_date(s) => "date",
+            Value::String(_) => "text",
+            Value::Null | Value::Array(_) => return,
+        };
+        self.fields.insert(
+            path.to_owned(),
+            FieldType {
+                name: type_name.to_owned(),
+                format: None,
+            },
+        );
+    }
+}
+
+impl FieldType {
+    fn has_properties(&self) -> bool {
+        matches!(self.name.as_str(), "object" | "nested")
+    }
+
+    fn is_keyword(&self) -> bool {
+        matches!(self.name.as_str(), "keyword" | "wildcard" | "constant_keyword")
+    }
+
+    fn accepts(&self, value: &Value) -> bool {
+        match self.name.as_str() {
+            "object" | "nested" => value.is_object(),
+            "long" | "integer" | "short" | "byte" | "unsigned_long" | "float" | "double"
+            | "half_float" | "scaled_float" => match value {
+                Value::Number(_) => true,
+                Value::String(s) => s.trim().parse::<f64>().is_ok(),
+                _ => false,
+            },
+            "keyword" | "text" | "wildcard" | "constant_keyword" | "match_only_text" => {
+                !value.is_object()
+            }
+            "boolean" => match value {
+                Value::Bool(_) => true,
+                Value::String(s) => matches!(s.as_str(), "true" | "false" | ""),
+                _ => false,
+            },
+            "ip" => match value {
+                Value::String(s) => s.parse::<IpAddr>().is_ok(),
+                _ => false,
+            },
+            "date_nanos" if exceeds_nanos_range(value) => false,
+            "date" | "date_nanos" => match value {
+                Value::Number(_) => true,
+                Value::String(s) => match &self.format {
+                    None => looks_like_date(s) || s.parse::<i64>().is_ok(),
+                    Some(formats) => formats.split("||").any(|format| match format {
+                        f if f.starts_with("epoch_") => s.parse::<f64>().is_ok(),
+                        f if f.contains("date_optional_time") => looks_like_date(s),
+                        // we do not know enough about custom formats to decide
+                        _ => true,
+                    }),
+                },
+                _ => false,
+            },
+
+            // we do not know enough about the other types to decide
+            _ => true,
+        }
+    }
+}
+
+/// tests if `value` is a timestamp which `date_nanos` cannot store, because
+/// it is before 1970 or after 2262
+fn exceeds_nanos_range(value: &Value) -> bool {
+    Timestamp::try_from(value).is_ok_and(|ts| ts.timestamp_nanos().is_none_or(|nanos| nanos < 0))
+}
+
+/// tests if elasticsearch's date detection would map `value` as a date
+fn looks_like_date(value: &str) -> bool {
+    DateTime::parse_from_rfc3339(value).is_ok()
+        || NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").is_ok()
+        || NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+}
@@ -0,0 +1,96 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use anyhow::{bail, Result};
+use serde_json::{json, Value};
+
+/// Definition of an elasticsearch ingest pipeline, which enriches documents
+/// on the server side while they are being indexed.
+///
+/// ```
+/// use es4forensics::Pipeline;
+///
+/// let pipeline = Pipeline::with_id("case-2024-017".to_string())
+///     .with_ingest_timestamp()
+///     .with_case("2024-017")
+///     .with_analyst("jdoe");
+/// assert_eq!(pipeline.body()["processors"].as_array().unwrap().len(), 3);
+/// ```
+pub struct Pipeline {
+    id: String,
+    body: Value,
+}
+
+impl Pipeline {
+    pub fn with_id(id: String) -> Self {
+        Self {
+            id,
+            body: json!({
+                "description": "created by es4forensics",
+                "processors": []
+            }),
+        }
+    }
+
+    /// loads the pipeline definition (`description`, `processors`, ...) from a JSON file
+    pub fn from_file<P: AsRef<Path>>(id: String, path: P) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Self::from_definition(id, serde_json::from_reader(reader)?)
+    }
+
+    pub fn from_definition(id: String, body: Value) -> Result<Self> {
+        match body.get("processors") {
+            Some(Value::Array(_)) => Ok(Self { id, body }),
+            _ => bail!("pipeline definition must contain an array of processors"),
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.body["description"] = json!(description);
+        self
+    }
+
+    /// sets `event.ingested` to the time when the document was indexed
+    pub fn with_ingest_timestamp(self) -> Self {
+        self.with_processor(json!({
+            "set": {
+                "field": "event.ingested",
+                "value": "{{{_ingest.timestamp}}}"
+            }
+        }))
+    }
+
+    /// sets `forensics.case` in every document
+    pub fn with_case(self, case: &str) -> Self {
+        self.with_field("forensics.case", case)
+    }
+
+    /// sets `forensics.analyst` in every document
+    pub fn with_analyst(self, analyst: &str) -> Self {
+        self.with_field("forensics.analyst", analyst)
+    }
+
+    /// sets `field` to the constant value `value` in every document
+    pub fn with_field(self, field: &str, value: &str) -> Self {
+        self.with_processor(json!({
+            "set": {
+                "field": field,
+                "value": value
+            }
+        }))
+    }
+
+    pub fn with_processor(mut self, processor: Value) -> Self {
+        if let Some(processors) = self.body["processors"].as_array_mut() {
+            processors.push(processor);
+        }
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn body(&self) -> &Value {
+        &self.body
+    }
+}
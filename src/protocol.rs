@@ -3,16 +3,13 @@ use std::fmt::Display;
 use clap::ValueEnum;
 
 
-#[derive(ValueEnum, Clone)]
+#[derive(ValueEnum, Clone, Default)]
 pub enum Protocol {
     Http,
+    #[default]
     Https,
 }
 
-impl Default for Protocol {
-    fn default() -> Self { Protocol::Https }
-}
-
 impl Display for Protocol {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -0,0 +1,57 @@
+use serde_json::{json, Value};
+
+use crate::{
+    utils::json::{remove_field, set_field},
+    EvidenceFile,
+};
+
+/// Describes where a document came from, so that every document can be traced
+/// back to the exact position in the evidence it has been generated from.
+///
+/// The fields are stored in `log.file.path`, `log.offset` (the byte offset of
+/// the record in the uncompressed input) and `forensics.source.*`.
+///
+/// These fields are not part of the content hash (see [`crate::IdStrategy::ContentHash`]),
+/// so that the same record gets the same id regardless of where the evidence has been read from.
+pub struct Provenance {
+    file_path: String,
+    sha256: Option<String>,
+    parser: String,
+}
+
+/// fields which are set by [`Provenance::apply`]
+const PROVENANCE_FIELDS: [&str; 3] = ["log.file.path", "log.offset", "forensics.source"];
+
+impl Provenance {
+    pub fn new(evidence: &EvidenceFile, parser: &str) -> Self {
+        Self {
+            file_path: evidence.path.clone(),
+            sha256: evidence.sha256.clone(),
+            parser: parser.to_owned(),
+        }
+    }
+
+    /// adds the provenance fields of the record in line `line_number`, which
+    /// starts at byte `offset`, to `document`
+    pub fn apply(&self, document: &mut Value, line_number: u64, offset: u64) {
+        let fields = [
+            ("log.file.path", json!(self.file_path)),
+            ("log.offset", json!(offset)),
+            ("forensics.source.line", json!(line_number)),
+            ("forensics.source.parser", json!(self.parser)),
+            ("forensics.source.sha256", json!(self.sha256)),
+        ];
+        for (field, value) in fields {
+            if !set_field(document, field, value) {
+                log::warn!("unable to set '{field}', because a parent field is not an object");
+            }
+        }
+    }
+
+    /// removes all provenance fields from `document`
+    pub(crate) fn remove(document: &mut Value) {
+        for field in PROVENANCE_FIELDS {
+            remove_field(document, field);
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use anyhow::Result;
+use csv::StringRecord;
+use es4forensics::InputDigest;
 use std::{
+    fmt::Display,
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Read},
 };
 
 #[cfg(feature = "gzip")]
@@ -17,24 +20,34 @@ impl StreamSource {
         if filename == "-" {
             Ok(StreamSource::Stdin)
         } else {
-            let file = BufReader::new(File::open(filename)?);
-
-            #[cfg(not(feature = "gzip"))]
-            let reader: Box<dyn BufRead + Send> = Box::new(file);
+            let file = File::open(filename)?;
+            Ok(StreamSource::File(Self::open(filename, file)))
+        }
+    }
 
-            #[cfg(feature = "gzip")]
-            let reader = Self::open_gzip(filename, file);
+    /// opens `filename` like [`StreamSource::from`], and passes all bytes which are
+    /// read (as they are stored, i.e. still compressed) to the returned digest
+    pub fn hashed(filename: &str) -> Result<(Self, InputDigest)> {
+        let digest = InputDigest::default();
+        let reader: Box<dyn BufRead + Send> = if filename == "-" {
+            Box::new(BufReader::new(digest.reader(std::io::stdin())))
+        } else {
+            Self::open(filename, digest.reader(File::open(filename)?))
+        };
+        Ok((StreamSource::File(reader), digest))
+    }
 
-            Ok(StreamSource::File(reader))
-        }
+    #[cfg(not(feature = "gzip"))]
+    fn open<R: Read + Send + 'static>(_filename: &str, file: R) -> Box<dyn BufRead + Send> {
+        Box::new(BufReader::new(file))
     }
 
     #[cfg(feature = "gzip")]
-    fn open_gzip<R: Read + Send + 'static>(filename: &str, file: R) -> Box<dyn Read + Send> {
+    fn open<R: Read + Send + 'static>(filename: &str, file: R) -> Box<dyn BufRead + Send> {
         if filename.ends_with(".gz") {
-            Box::new(GzDecoder::new(file))
+            Box::new(BufReader::new(GzDecoder::new(file)))
         } else {
-            Box::new(file)
+            Box::new(BufReader::new(file))
         }
     }
 }
@@ -47,3 +60,111 @@ impl From<StreamSource> for Box<dyn BufRead + Send> {
         }
     }
 }
+
+/// A line of the input, together with its position
+pub(crate) struct SourceLine {
+    /// number of the line, starting at 1
+    pub number: u64,
+
+    /// byte offset of the start of the line in the (uncompressed) input
+    pub offset: u64,
+
+    pub text: SourceText,
+}
+
+/// content of a [`SourceLine`]
+pub(crate) enum SourceText {
+    Line(String),
+
+    /// a CSV record, which may span several lines if it contains quoted line breaks
+    Record(StringRecord),
+}
+
+impl Display for SourceText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceText::Line(line) => write!(f, "{line}"),
+            SourceText::Record(record) => write!(f, "{}", record.iter().collect::<Vec<_>>().join(",")),
+        }
+    }
+}
+
+/// Iterator over the lines of the input, which keeps track of the current position.
+/// CSV input is read record by record, so that quoted fields may contain line breaks.
+pub(crate) enum SourceLines {
+    Lines {
+        reader: Box<dyn BufRead + Send>,
+        number: u64,
+        offset: u64,
+    },
+    Records(csv::Reader<Box<dyn BufRead + Send>>),
+}
+
+impl SourceLines {
+    pub fn csv(source: StreamSource) -> Self {
+        let reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(source.into());
+        Self::Records(reader)
+    }
+
+    fn next_record(reader: &mut csv::Reader<Box<dyn BufRead + Send>>) -> Option<std::io::Result<SourceLine>> {
+        let mut record = StringRecord::new();
+        match reader.read_record(&mut record) {
+            Err(why) => Some(Err(why.into())),
+            Ok(false) => None,
+            Ok(true) => {
+                let position = record.position().expect("records read from a reader have a position");
+                Some(Ok(SourceLine {
+                    number: position.line(),
+                    offset: position.byte(),
+                    text: SourceText::Record(record),
+                }))
+            }
+        }
+    }
+}
+
+impl From<StreamSource> for SourceLines {
+    fn from(me: StreamSource) -> Self {
+        Self::Lines {
+            reader: me.into(),
+            number: 0,
+            offset: 0,
+        }
+    }
+}
+
+impl Iterator for SourceLines {
+    type Item = std::io::Result<SourceLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (reader, number, offset) = match self {
+            SourceLines::Lines { reader, number, offset } => (reader, number, offset),
+            SourceLines::Records(reader) => return Self::next_record(reader),
+        };
+        let mut text = String::new();
+        match reader.read_line(&mut text) {
+            Err(why) => Some(Err(why)),
+            Ok(0) => None,
+            Ok(length) => {
+                let line_offset = *offset;
+                *offset += length as u64;
+                *number += 1;
+
+                if text.ends_with('\n') {
+                    text.pop();
+                    if text.ends_with('\r') {
+                        text.pop();
+                    }
+                }
+                Some(Ok(SourceLine {
+                    number: *number,
+                    offset: line_offset,
+                    text: SourceText::Line(text),
+                }))
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::Result;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::{utils::json::get_field, Timestamp};
+
+/// A pattern which is matched against `file.path`
+pub enum PathPattern {
+    /// shell style pattern, where `*` also matches `/`
+    Glob(glob::Pattern),
+    Regex(Regex),
+}
+
+impl PathPattern {
+    pub fn glob(pattern: &str) -> Result<Self> {
+        Ok(Self::Glob(glob::Pattern::new(pattern)?))
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self> {
+        Ok(Self::Regex(Regex::new(pattern)?))
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        match self {
+            Self::Glob(pattern) => pattern.matches(path),
+            Self::Regex(regex) => regex.is_match(path),
+        }
+    }
+}
+
+/// Selects the documents of a timeline which are relevant for an investigation,
+/// before they are written anywhere.
+///
+/// ```
+/// use es4forensics::{PathPattern, TimelineFilter};
+/// use serde_json::json;
+///
+/// let filter = TimelineFilter::default()
+///     .with_exclude_path(PathPattern::glob("/usr/share/*").unwrap());
+/// assert!(filter.matches(&json!({"@timestamp": 0, "file": {"path": "/etc/passwd"}})));
+/// assert!(!filter.matches(&json!({"@timestamp": 0, "file": {"path": "/usr/share/doc/a"}})));
+/// ```
+#[derive(Default)]
+pub struct TimelineFilter {
+    start: Option<Timestamp>,
+    end: Option<Timestamp>,
+    include_paths: Vec<PathPattern>,
+    exclude_paths: Vec<PathPattern>,
+    skip_empty_timestamps: bool,
+}
+
+impl TimelineFilter {
+    /// only select documents whose `@timestamp` is not before `start`
+    pub fn with_start(mut self, start: Timestamp) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// only select documents whose `@timestamp` is not after `end`
+    pub fn with_end(mut self, end: Timestamp) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// only select documents whose `file.path` matches this or another include
+    /// pattern. Documents without `file.path` are not selected.
+    pub fn with_include_path(mut self, pattern: PathPattern) -> Self {
+        self.include_paths.push(pattern);
+        self
+    }
+
+    /// do not select documents whose `file.path` matches `pattern`
+    pub fn with_exclude_path(mut self, pattern: PathPattern) -> Self {
+        self.exclude_paths.push(pattern);
+        self
+    }
+
+    /// do not select bodyfile and mactime entries whose timestamps are all unset, i.e.
+    /// `0` or `-1` (seconds since the epoch). [`crate::TimelineParser`] checks this on
+    /// the values of the input, before they are converted from their timezone and
+    /// corrected by a [`crate::ClockCorrection`], so it does not apply to [`TimelineFilter::matches`].
+    pub fn with_skip_empty_timestamps(mut self, skip_empty_timestamps: bool) -> Self {
+        self.skip_empty_timestamps = skip_empty_timestamps;
+        self
+    }
+
+    pub fn skips_empty_timestamps(&self) -> bool {
+        self.skip_empty_timestamps
+    }
+
+    /// returns `true` if `document` should be kept. If a time window has been
+    /// configured, documents without a valid `@timestamp` are not selected.
+    pub fn matches(&self, document: &Value) -> bool {
+        self.matches_fields(
+            || Timestamp::try_from(&document["@timestamp"]).ok(),
+            get_field(document, "file.path").and_then(|p| p.as_str()),
+        )
+    }
+
+    /// returns `true` if a document with this `@timestamp` and `file.path` should be
+    /// kept. `timestamp` is only evaluated if a time window has been configured.
+    pub(crate) fn matches_fields(&self, timestamp: impl FnOnce() -> Option<Timestamp>, path: Option<&str>) -> bool {
+        self.matches_time(timestamp) && self.matches_path(path)
+    }
+
+    fn matches_time(&self, timestamp: impl FnOnce() -> Option<Timestamp>) -> bool {
+        if self.start.is_none() && self.end.is_none() {
+            return true;
+        }
+        let ts = match timestamp() {
+            Some(ts) => ts,
+            None => return false,
+        };
+        !(self.start.as_ref().is_some_and(|start| &ts < start)
+            || self.end.as_ref().is_some_and(|end| &ts > end))
+    }
+
+    fn matches_path(&self, path: Option<&str>) -> bool {
+        if self.include_paths.is_empty() && self.exclude_paths.is_empty() {
+            return true;
+        }
+        let path = match path {
+            Some(path) => path,
+            None => return self.include_paths.is_empty(),
+        };
+        (self.include_paths.is_empty() || self.include_paths.iter().any(|p| p.matches(path)))
+            && !self.exclude_paths.iter().any(|p| p.matches(path))
+    }
+}
+
+/// tools use `0` or `-1` (seconds since the epoch) for timestamps which are not set
+pub(crate) fn is_unset(seconds: i64) -> bool {
+    matches!(seconds, 0 | -1)
+}
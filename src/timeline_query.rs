@@ -0,0 +1,99 @@
+use chrono::Duration;
+use serde_json::{json, Value};
+
+use crate::Timestamp;
+
+/// Filter criteria to select documents from an index
+///
+/// ```
+/// use es4forensics::{TimelineQuery, Timestamp};
+///
+/// let start = Timestamp::try_from((1577092511, &chrono_tz::Tz::UTC)).unwrap();
+/// let query = TimelineQuery::default()
+///     .with_start(start)
+///     .with_query_string("file.path:*.exe".to_string());
+/// assert_eq!(query.to_query()["bool"]["filter"].as_array().unwrap().len(), 2);
+/// ```
+#[derive(Default, Clone)]
+pub struct TimelineQuery {
+    start: Option<Timestamp>,
+    end: Option<Timestamp>,
+    query_string: Option<String>,
+    hosts: Vec<String>,
+    tags: Vec<String>,
+}
+
+impl TimelineQuery {
+    /// only select documents whose `@timestamp` is not before `start`
+    pub fn with_start(mut self, start: Timestamp) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// only select documents whose `@timestamp` is not after `end`
+    pub fn with_end(mut self, end: Timestamp) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// only select documents whose `@timestamp` differs at most `window` from `pivot`
+    pub fn with_window_around(mut self, pivot: &Timestamp, window: Duration) -> Self {
+        let pivot = pivot.as_datetime();
+        self.start = pivot.checked_sub_signed(window).map(Timestamp::from);
+        self.end = pivot.checked_add_signed(window).map(Timestamp::from);
+        self
+    }
+
+    /// only select documents which have been created on one of the given hosts
+    pub fn with_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.hosts = hosts;
+        self
+    }
+
+    /// only select documents which have all of the given tags
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// only select documents which match a query in Lucene syntax
+    pub fn with_query_string(mut self, query_string: String) -> Self {
+        self.query_string = Some(query_string);
+        self
+    }
+
+    /// returns the elasticsearch query DSL representation of this query
+    pub fn to_query(&self) -> Value {
+        let mut filters = Vec::new();
+
+        if self.start.is_some() || self.end.is_some() {
+            // `@timestamp` is mapped as `date_nanos`, so the bounds keep all fractional digits
+            let mut range = json!({"format": "strict_date_optional_time_nanos"});
+            if let Some(start) = &self.start {
+                range["gte"] = json!(start.to_iso8601());
+            }
+            if let Some(end) = &self.end {
+                range["lte"] = json!(end.to_iso8601());
+            }
+            filters.push(json!({"range": {"@timestamp": range}}));
+        }
+
+        if !self.hosts.is_empty() {
+            filters.push(json!({"terms": {"host.name": self.hosts}}));
+        }
+
+        for tag in self.tags.iter() {
+            filters.push(json!({"term": {"tags": tag}}));
+        }
+
+        if let Some(query_string) = &self.query_string {
+            filters.push(json!({"query_string": {"query": query_string}}));
+        }
+
+        if filters.is_empty() {
+            json!({"match_all": {}})
+        } else {
+            json!({"bool": {"filter": filters}})
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Lines, Seek, Write},
+};
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::Timestamp;
+
+/// A document together with the key it is sorted by. Documents without a valid
+/// `@timestamp` are sorted before all other documents.
+struct SortEntry {
+    ts: Option<Timestamp>,
+
+    /// index of the run the document has been read from, which keeps the sort stable
+    run: usize,
+    document: Value,
+}
+
+impl SortEntry {
+    fn new(document: Value, run: usize) -> Self {
+        Self {
+            ts: Timestamp::try_from(&document["@timestamp"]).ok(),
+            run,
+            document,
+        }
+    }
+}
+
+impl PartialEq for SortEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SortEntry {}
+
+impl PartialOrd for SortEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.ts, self.run).cmp(&(&other.ts, other.run))
+    }
+}
+
+/// number of runs which are merged into one run of the next level, which limits
+/// the number of temporary files which are open at the same time
+const MERGE_FAN_IN: usize = 16;
+
+/// A sorted temporary file
+struct Run {
+    file: File,
+
+    /// number of times its documents have been merged
+    level: u32,
+}
+
+/// Sorts documents by `@timestamp`, using an external merge sort: at most
+/// `buffer_size` documents are kept in memory, all others are written into
+/// sorted temporary files, which are merged when the documents are read.
+/// Whenever there are [`MERGE_FAN_IN`] files of the same level, they are merged
+/// into one file of the next level, so that only a few files are open at a time.
+///
+/// Documents with the same timestamp keep the order in which they have been added.
+///
+/// ```
+/// use es4forensics::TimelineSorter;
+/// use serde_json::json;
+///
+/// let mut sorter = TimelineSorter::new(2);
+/// for ts in ["2019-12-23T09:15:13Z", "2019-12-23T09:15:11Z", "2019-12-23T09:15:12Z"] {
+///     sorter.add(json!({"@timestamp": ts})).unwrap();
+/// }
+/// let sorted: Vec<_> = sorter.into_sorted().unwrap().map(|d| d.unwrap()).collect();
+/// assert_eq!(sorted[0]["@timestamp"], "2019-12-23T09:15:11Z");
+/// assert_eq!(sorted[2]["@timestamp"], "2019-12-23T09:15:13Z");
+/// ```
+pub struct TimelineSorter {
+    buffer_size: usize,
+    buffer: Vec<SortEntry>,
+
+    /// runs in the order in which their documents have been added. Their levels
+    /// never increase, so runs of the same level are always at the end.
+    runs: Vec<Run>,
+}
+
+impl TimelineSorter {
+    pub fn new(buffer_size: usize) -> Self {
+        Self {
+            buffer_size: buffer_size.max(1),
+            buffer: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, document: Value) -> Result<()> {
+        self.buffer.push(SortEntry::new(document, 0));
+        if self.buffer.len() >= self.buffer_size {
+            self.write_run()?;
+        }
+        Ok(())
+    }
+
+    /// returns the number of temporary files which are currently kept
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// writes the sorted buffer into an (anonymous) temporary file
+    fn write_run(&mut self) -> Result<()> {
+        self.buffer.sort();
+        let file = Self::write_file(self.buffer.drain(..).map(|entry| Ok(entry.document)))?;
+        self.runs.push(Run { file, level: 0 });
+        log::debug!("wrote sorted run #{}", self.runs.len());
+        self.merge_full_levels()
+    }
+
+    /// merges the last runs while there are enough of them with the same level
+    fn merge_full_levels(&mut self) -> Result<()> {
+        while let Some(level) = self.runs.last().map(|run| run.level) {
+            let count = self.runs.iter().rev().take_while(|run| run.level == level).count();
+            if count < MERGE_FAN_IN {
+                break;
+            }
+            let start = self.runs.len() - count;
+            let runs = self.runs.drain(start..).map(|run| run.file).collect();
+            let file = Self::write_file(SortedDocuments::merge(runs)?)?;
+            self.runs.push(Run { file, level: level + 1 });
+            log::debug!("merged {count} sorted runs of level {level}");
+        }
+        Ok(())
+    }
+
+    fn write_file(documents: impl Iterator<Item = Result<Value>>) -> Result<File> {
+        let mut writer = BufWriter::new(tempfile::tempfile()?);
+        for document in documents {
+            serde_json::to_writer(&mut writer, &document?)?;
+            writer.write_all(b"\n")?;
+        }
+        let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.rewind()?;
+        Ok(file)
+    }
+
+    /// returns all documents, sorted by `@timestamp`
+    pub fn into_sorted(mut self) -> Result<SortedDocuments> {
+        if self.runs.is_empty() {
+            // everything fits into memory
+            self.buffer.sort();
+            return Ok(SortedDocuments {
+                buffer: self.buffer.into_iter(),
+                runs: Vec::new(),
+                heap: BinaryHeap::new(),
+            });
+        }
+        if !self.buffer.is_empty() {
+            self.write_run()?;
+        }
+        SortedDocuments::merge(self.runs.into_iter().map(|run| run.file).collect())
+    }
+}
+
+/// Iterator over the documents of a [`TimelineSorter`], sorted by `@timestamp`
+pub struct SortedDocuments {
+    buffer: std::vec::IntoIter<SortEntry>,
+    runs: Vec<Lines<BufReader<File>>>,
+
+    /// contains the next document of every run which has not been exhausted
+    heap: BinaryHeap<Reverse<SortEntry>>,
+}
+
+impl SortedDocuments {
+    /// merges sorted runs, which must be given in the order of their documents
+    fn merge(runs: Vec<File>) -> Result<Self> {
+        let mut sorted = Self {
+            buffer: Vec::new().into_iter(),
+            runs: runs.into_iter().map(|f| BufReader::new(f).lines()).collect(),
+            heap: BinaryHeap::new(),
+        };
+        for run in 0..sorted.runs.len() {
+            sorted.read_next(run)?;
+        }
+        Ok(sorted)
+    }
+
+    fn read_next(&mut self, run: usize) -> Result<()> {
+        if let Some(line) = self.runs[run].next() {
+            let document = serde_json::from_str(&line?)?;
+            self.heap.push(Reverse(SortEntry::new(document, run)));
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for SortedDocuments {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(entry) = self.buffer.next() {
+            return Some(Ok(entry.document));
+        }
+        let Reverse(entry) = self.heap.pop()?;
+        match self.read_next(entry.run) {
+            Ok(()) => Some(Ok(entry.document)),
+            Err(why) => Some(Err(why)),
+        }
+    }
+}
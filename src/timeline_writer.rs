@@ -0,0 +1,256 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::DefaultHasher, BinaryHeap, HashSet},
+    hash::{Hash, Hasher},
+    io::Write,
+};
+
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat};
+use serde_json::Value;
+
+/// format of exported timelines
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ExportFormat {
+    /// one ECS document per line
+    #[default]
+    Json,
+
+    /// mactime-style CSV (`Date,Size,Type,Mode,UID,GID,Meta,File Name`)
+    Csv,
+
+    /// bodyfile lines, created from all documents which contain `file.*` fields
+    Bodyfile,
+
+    /// compact table, containing timestamp, host, tags and message
+    Table,
+}
+
+const MACTIME_DATE_FORMAT: &str = "%a %b %d %Y %H:%M:%S";
+const MACTIME_HEADER: [&str; 8] = ["Date", "Size", "Type", "Mode", "UID", "GID", "Meta", "File Name"];
+
+enum Output<W: Write> {
+    Lines(W),
+    Csv(Box<csv::Writer<W>>),
+}
+
+/// Writes ECS documents in one of the supported [`ExportFormat`]s
+pub struct TimelineWriter<W: Write> {
+    format: ExportFormat,
+    output: Output<W>,
+
+    /// one bodyfile line yields up to four documents, but we want to export it only once
+    exported_bodyfile_lines: ExportedLines,
+}
+
+/// Remembers which bodyfile lines have already been exported.
+///
+/// Only the hashes of the lines are stored. Because the documents are sorted by
+/// `@timestamp`, a line can be forgotten as soon as the last of its timestamps
+/// has passed. If the documents are not sorted, a line might be exported more than once.
+#[derive(Default)]
+struct ExportedLines {
+    hashes: HashSet<u64>,
+
+    /// hashes of the lines, ordered by the last timestamp of the line
+    expiries: BinaryHeap<Reverse<(i64, u64)>>,
+}
+
+impl ExportedLines {
+    /// returns `false` if `line` has already been exported
+    fn insert(&mut self, line: &str, now: Option<i64>, last_timestamp: Option<i64>) -> bool {
+        if let Some(now) = now {
+            while let Some(Reverse((expiry, hash))) = self.expiries.peek().copied() {
+                if expiry >= now {
+                    break;
+                }
+                self.expiries.pop();
+                self.hashes.remove(&hash);
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        line.hash(&mut hasher);
+        let hash = hasher.finish();
+        if !self.hashes.insert(hash) {
+            return false;
+        }
+        self.expiries
+            .push(Reverse((last_timestamp.unwrap_or(i64::MAX), hash)));
+        true
+    }
+}
+
+impl<W: Write> TimelineWriter<W> {
+    pub fn new(format: ExportFormat, writer: W) -> Result<Self> {
+        let output = match format {
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(writer);
+                writer.write_record(MACTIME_HEADER)?;
+                Output::Csv(Box::new(writer))
+            }
+            _ => Output::Lines(writer),
+        };
+        Ok(Self {
+            format,
+            output,
+            exported_bodyfile_lines: ExportedLines::default(),
+        })
+    }
+
+    pub fn write_document(&mut self, document: &Value) -> Result<()> {
+        match self.format {
+            ExportFormat::Json => self.write_line(&document.to_string()),
+            ExportFormat::Csv => {
+                if let Output::Csv(writer) = &mut self.output {
+                    writer.write_record(Self::mactime_record(document))?;
+                }
+                Ok(())
+            }
+            ExportFormat::Bodyfile => match Self::bodyfile_line(document) {
+                Some(line) => {
+                    let now = timestamp_millis(&document["@timestamp"]);
+                    let last_timestamp = ["accessed", "mtime", "ctime", "created"]
+                        .iter()
+                        .filter_map(|field| timestamp_millis(&document["file"][field]))
+                        .chain(now)
+                        .max();
+                    if self.exported_bodyfile_lines.insert(&line, now, last_timestamp) {
+                        self.write_line(&line)
+                    } else {
+                        Ok(())
+                    }
+                }
+                None => Ok(()),
+            },
+            ExportFormat::Table => self.write_line(&Self::table_row(document)),
+        }
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        match &mut self.output {
+            Output::Lines(writer) => writer.flush()?,
+            Output::Csv(writer) => writer.flush()?,
+        }
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        if let Output::Lines(writer) = &mut self.output {
+            writer.write_all(line.as_bytes())?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn mactime_record(document: &Value) -> Vec<String> {
+        let file = &document["file"];
+        let date = timestamp_millis(&document["@timestamp"])
+            .and_then(DateTime::from_timestamp_millis)
+            .map(|ts| ts.format(MACTIME_DATE_FORMAT).to_string())
+            .unwrap_or_default();
+        let name = file["path"]
+            .as_str()
+            .or_else(|| document["message"].as_str())
+            .unwrap_or_default();
+        vec![
+            date,
+            value_to_string(&file["size"], "0"),
+            macb_short(document),
+            value_to_string(&file["mode"], ""),
+            value_to_string(&file["uid"], "0"),
+            value_to_string(&file["gid"], "0"),
+            value_to_string(&file["inode"], "0"),
+            name.to_owned(),
+        ]
+    }
+
+    fn table_row(document: &Value) -> String {
+        let date = timestamp_millis(&document["@timestamp"])
+            .and_then(DateTime::from_timestamp_millis)
+            .map(|ts| ts.to_rfc3339_opts(SecondsFormat::Millis, true))
+            .unwrap_or_default();
+        let tags = match document["tags"].as_array() {
+            Some(tags) => tags
+                .iter()
+                .map(|t| value_to_string(t, ""))
+                .collect::<Vec<_>>()
+                .join(","),
+            None => String::new(),
+        };
+        format!(
+            "{:24}  {:16}  {:16}  {}",
+            date,
+            value_to_string(&document["host"]["name"], "-"),
+            tags,
+            value_to_string(&document["message"], "")
+        )
+    }
+
+    fn bodyfile_line(document: &Value) -> Option<String> {
+        let file = document.get("file")?;
+        let path = file["path"].as_str()?;
+        let time = |field: &str| {
+            timestamp_millis(&file[field])
+                .map(|ts| ts.div_euclid(1000))
+                .unwrap_or(-1)
+        };
+        Some(format!(
+            "0|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}",
+            path,
+            value_to_string(&file["inode"], "0"),
+            value_to_string(&file["mode"], ""),
+            value_to_string(&file["uid"], "0"),
+            value_to_string(&file["gid"], "0"),
+            value_to_string(&file["size"], "0"),
+            time("accessed"),
+            time("mtime"),
+            time("ctime"),
+            time("created"),
+        ))
+    }
+}
+
+/// reads a timestamp which has been stored either as number of milliseconds or as RFC 3339 string
+fn timestamp_millis(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|ts| ts.timestamp_millis()),
+        _ => None,
+    }
+}
+
+fn value_to_string(value: &Value, default: &str) -> String {
+    match value {
+        Value::Null => default.to_owned(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+/// returns `file.macb_short` or, if it is missing, calculates it from the file timestamps
+fn macb_short(document: &Value) -> String {
+    let file = &document["file"];
+    if let Some(macb) = file["macb_short"].as_str() {
+        return macb.to_owned();
+    }
+    let ts = timestamp_millis(&document["@timestamp"]);
+    let flag = |field: &str, c: char| {
+        if ts.is_some() && timestamp_millis(&file[field]) == ts {
+            c
+        } else {
+            '.'
+        }
+    };
+    [
+        flag("mtime", 'm'),
+        flag("accessed", 'a'),
+        flag("ctime", 'c'),
+        flag("created", 'b'),
+    ]
+    .into_iter()
+    .collect()
+}
@@ -1,29 +1,112 @@
-use chrono::{DateTime, Utc, TimeZone, NaiveDateTime, LocalResult};
+use chrono::{DateTime, Duration, Utc, TimeZone, LocalResult, Offset, SecondsFormat, NaiveDate, NaiveDateTime};
 use chrono_tz::Tz;
 use serde::Serialize;
 use serde_json::{Value, json};
-use std::hash::Hash;
-use anyhow::{anyhow, Result};
+use std::cmp::Ordering as CmpOrdering;
+use std::hash::{Hash, Hasher};
+use anyhow::{anyhow, bail, Result};
 
-#[derive(Eq, PartialEq, Clone, Hash, PartialOrd, Ord)]
+use crate::{
+    utils::json::{get_field, set_field},
+    AmbiguousTime, DstAdjustment, DstPolicy, NonexistentTime,
+};
+
+/// format which is used to write timestamps into documents
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum TimestampFormat {
+    /// ISO-8601 string in UTC, with all available fractional digits (up to nanoseconds)
+    #[default]
+    Iso8601,
+
+    /// number of milliseconds since the epoch; sub-millisecond precision is lost
+    EpochMillis,
+}
+
+/// date fields of the generated documents
+pub(crate) const DATE_FIELDS: [&str; 8] = [
+    "@timestamp",
+    "event.created",
+    "event.start",
+    "event.end",
+    "file.accessed",
+    "file.created",
+    "file.ctime",
+    "file.mtime",
+];
+
+impl TimestampFormat {
+    /// writes all date fields of `document` in this format, including the original values
+    /// which have been kept by a [`crate::ClockCorrection`]. Values which cannot be read are
+    /// kept unchanged.
+    pub fn apply(&self, document: &mut Value) {
+        let original_fields = DATE_FIELDS
+            .iter()
+            .map(|field| format!("forensics.clock_correction.original.{field}"));
+        for field in DATE_FIELDS.iter().map(|f| f.to_string()).chain(original_fields) {
+            let formatted = match get_field(document, &field) {
+                Some(value) if !value.is_null() => match Timestamp::try_from(value) {
+                    Ok(ts) => ts.to_value(*self),
+                    Err(_) => continue,
+                },
+                _ => continue,
+            };
+            set_field(document, &field, formatted);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Timestamp {
-    ts: i64,
+    ts: DateTime<Utc>,
+
+    /// is not considered when comparing timestamps
+    dst_adjustment: Option<DstAdjustment>,
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.ts == other.ts
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl Hash for Timestamp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ts.hash(state);
+    }
+}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.ts.cmp(&other.ts)
+    }
 }
 
+/// timestamps are serialized as ISO-8601 strings, see [`TimestampFormat::apply`]
+/// for writing them in another format
 impl Serialize for Timestamp {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer {
-        serializer.serialize_i64(self.ts)
+        serializer.serialize_str(&self.to_iso8601())
     }
 }
 
 impl<Tz> From<DateTime<Tz>> for Timestamp where Tz: TimeZone {
     fn from(d: DateTime<Tz>) -> Self {
         let ts = d.with_timezone(&Utc);
-        log::trace!("converting {:?} to {}", d, ts.timestamp_millis());
+        log::trace!("converting {:?} to {}", d, ts);
         Self {
-            ts: ts.timestamp_millis(),
+            ts,
+            dst_adjustment: None,
         }
     }
 }
@@ -33,31 +116,260 @@ impl TryFrom<(i64, &Tz)> for Timestamp {
     type Error = anyhow::Error;
 
     fn try_from((unix_ts, src_tz): (i64, &Tz)) -> Result<Self, Self::Error> {
+        Self::try_from((unix_ts, src_tz, DstPolicy::default()))
+    }
+}
+
+/// interprets `unix_ts` as local time in `src_tz`, resolving times which are
+/// affected by DST transitions according to the [`DstPolicy`]
+impl TryFrom<(i64, &Tz, DstPolicy)> for Timestamp {
+    type Error = anyhow::Error;
+
+    fn try_from((unix_ts, src_tz, policy): (i64, &Tz, DstPolicy)) -> Result<Self, Self::Error> {
 
-        let ts = match src_tz.from_local_datetime(&NaiveDateTime::from_timestamp(unix_ts, 0)) {
-            LocalResult::None => {
-                return Err(anyhow!("INVALID DATETIME"));
-            }
-            LocalResult::Single(t) => t,
-            LocalResult::Ambiguous(t1, _t2) => t1,
+        let local_ts = match DateTime::from_timestamp(unix_ts, 0) {
+            Some(ts) => ts.naive_utc(),
+            None => return Err(anyhow!("INVALID DATETIME")),
         };
-        Ok(
-            Self {
-                ts: ts.timestamp_millis(),
-            }
-        )
+        Self::from_local(&local_ts, src_tz, policy)
     }
 }
 
 
 impl From<&Timestamp> for Value {
     fn from(ts: &Timestamp) -> Self {
-        json!(ts.ts)
+        json!(ts)
+    }
+}
+
+/// reads a timestamp which has been stored either as number of milliseconds or as ISO-8601 string
+impl TryFrom<&Value> for Timestamp {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(n) => match n.as_i64() {
+                Some(millis) => Self::from_unix_millis(millis),
+                None => Self::from_float_seconds(n.as_f64().unwrap_or(f64::NAN) / 1000.0, 0),
+            },
+            Value::String(s) => match s.parse::<i64>() {
+                Ok(millis) => Self::from_unix_millis(millis),
+                Err(_) => Self::from_iso8601(s),
+            },
+            v => bail!("invalid timestamp: {v}"),
+        }
     }
 }
 
 impl Timestamp {
     pub fn timestamp_millis(&self) -> i64 {
-        self.ts
+        self.ts.timestamp_millis()
+    }
+
+    /// returns the number of nanoseconds since the epoch, or `None` if the
+    /// timestamp is outside the range of `i64` (before 1677 or after 2262)
+    pub fn timestamp_nanos(&self) -> Option<i64> {
+        self.ts.timestamp_nanos_opt()
+    }
+
+    /// formats the timestamp like `2019-12-23T09:15:11.123456789Z`. The fraction
+    /// is written in groups of milli-, micro- or nanoseconds, as many as are needed,
+    /// and omitted if it is zero.
+    pub fn to_iso8601(&self) -> String {
+        self.ts.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+
+    /// returns the timestamp as it is written into documents in `format`
+    pub fn to_value(&self, format: TimestampFormat) -> Value {
+        match format {
+            TimestampFormat::Iso8601 => json!(self.to_iso8601()),
+            TimestampFormat::EpochMillis => json!(self.timestamp_millis()),
+        }
+    }
+
+    pub fn as_datetime(&self) -> &DateTime<Utc> {
+        &self.ts
+    }
+
+    /// returns how this timestamp has been adjusted because of a DST transition, if at all
+    pub fn dst_adjustment(&self) -> Option<DstAdjustment> {
+        self.dst_adjustment
+    }
+
+    /// interprets `local_ts` as local time in `src_tz`
+    pub fn from_local(local_ts: &NaiveDateTime, src_tz: &Tz, policy: DstPolicy) -> Result<Self> {
+        let (ts, adjustment) = match src_tz.from_local_datetime(local_ts) {
+            LocalResult::Single(t) => (t, None),
+            LocalResult::Ambiguous(t1, t2) => match policy.ambiguous {
+                AmbiguousTime::Earliest => (t1, Some(DstAdjustment::Ambiguous)),
+                AmbiguousTime::Latest => (t2, Some(DstAdjustment::Ambiguous)),
+                AmbiguousTime::Error => {
+                    bail!("local time {local_ts} is ambiguous in timezone {}", src_tz.name())
+                }
+            },
+            LocalResult::None => match policy.nonexistent {
+                NonexistentTime::ShiftForward => {
+                    // the offset which was valid a day before the transition
+                    let offset = src_tz
+                        .offset_from_utc_datetime(&(*local_ts - Duration::days(1)))
+                        .fix();
+                    let utc = *local_ts - Duration::seconds(offset.local_minus_utc() as i64);
+                    (utc.and_utc().with_timezone(src_tz), Some(DstAdjustment::Shifted))
+                }
+                NonexistentTime::Error => {
+                    bail!("local time {local_ts} does not exist in timezone {}", src_tz.name())
+                }
+            },
+        };
+        let mut timestamp = Self::from(ts);
+        timestamp.dst_adjustment = adjustment;
+        Ok(timestamp)
+    }
+
+    fn from_unix(secs: i64, nanos: u32) -> Result<Self> {
+        DateTime::from_timestamp(secs, nanos)
+            .map(Self::from)
+            .ok_or_else(|| anyhow!("timestamp out of range: {secs}.{nanos:09} seconds since the epoch"))
+    }
+
+    /// converts a number of (possibly fractional) seconds since `epoch_offset`,
+    /// which is given in seconds since the unix epoch
+    fn from_float_seconds(seconds: f64, epoch_offset: i64) -> Result<Self> {
+        if !seconds.is_finite() || seconds.abs() > i64::MAX as f64 / 2.0 {
+            bail!("timestamp out of range: {seconds}");
+        }
+        let secs = seconds.floor();
+        let nanos = ((seconds - secs) * 1e9).round() as u32;
+        let (secs, nanos) = match nanos {
+            1_000_000_000 => (secs as i64 + 1, 0),
+            n => (secs as i64, n),
+        };
+        Self::from_unix(epoch_offset + secs, nanos)
+    }
+
+    /// converts milliseconds since 1970-01-01 00:00:00 UTC
+    pub fn from_unix_millis(millis: i64) -> Result<Self> {
+        Self::from_unix(millis.div_euclid(1_000), (millis.rem_euclid(1_000) * 1_000_000) as u32)
+    }
+
+    /// converts microseconds since 1970-01-01 00:00:00 UTC
+    pub fn from_unix_micros(micros: i64) -> Result<Self> {
+        Self::from_unix(micros.div_euclid(1_000_000), (micros.rem_euclid(1_000_000) * 1_000) as u32)
+    }
+
+    /// converts nanoseconds since 1970-01-01 00:00:00 UTC
+    pub fn from_unix_nanos(nanos: i64) -> Result<Self> {
+        Self::from_unix(nanos.div_euclid(1_000_000_000), nanos.rem_euclid(1_000_000_000) as u32)
+    }
+
+    /// converts a Windows FILETIME, which counts 100 nanosecond intervals since
+    /// 1601-01-01 00:00:00 UTC. `0` is used by Windows as "not set" and is rejected.
+    ///
+    /// ```
+    /// use es4forensics::Timestamp;
+    ///
+    /// let ts = Timestamp::from_filetime(132215661111234567).unwrap();
+    /// assert_eq!(ts.to_iso8601(), "2019-12-23T09:15:11.123456700Z");
+    /// ```
+    pub fn from_filetime(filetime: u64) -> Result<Self> {
+        if filetime == 0 || filetime > i64::MAX as u64 {
+            bail!("invalid FILETIME: {filetime}");
+        }
+        let secs = (filetime / 10_000_000) as i64 - WINDOWS_EPOCH_OFFSET;
+        Self::from_unix(secs, (filetime % 10_000_000) as u32 * 100)
+    }
+
+    /// converts a WebKit/Chrome timestamp, which counts microseconds since
+    /// 1601-01-01 00:00:00 UTC. `0` is used by Chrome as "not set" and is rejected.
+    pub fn from_webkit(micros: i64) -> Result<Self> {
+        if micros <= 0 {
+            bail!("invalid WebKit timestamp: {micros}");
+        }
+        Self::from_unix(
+            micros / 1_000_000 - WINDOWS_EPOCH_OFFSET,
+            (micros % 1_000_000) as u32 * 1_000,
+        )
+    }
+
+    /// converts an HFS+ timestamp, which counts seconds since 1904-01-01 00:00:00 UTC
+    pub fn from_hfs_plus(secs: u32) -> Result<Self> {
+        if secs == 0 {
+            bail!("invalid HFS+ timestamp: {secs}");
+        }
+        Self::from_unix(secs as i64 - HFS_EPOCH_OFFSET, 0)
+    }
+
+    /// converts a Cocoa/Mac absolute time, which counts (fractional) seconds since
+    /// 2001-01-01 00:00:00 UTC
+    pub fn from_cocoa(seconds: f64) -> Result<Self> {
+        Self::from_float_seconds(seconds, COCOA_EPOCH_OFFSET)
+    }
+
+    /// converts an OLE Automation date, which counts days since 1899-12-30 00:00:00.
+    /// As in OLE, the fraction of negative values is the time *after* midnight of that day.
+    /// The result is rounded to microseconds, which is the precision of the format.
+    pub fn from_ole_automation(days: f64) -> Result<Self> {
+        if !days.is_finite() || !(OLE_MIN_DAYS..OLE_MAX_DAYS).contains(&days) {
+            bail!("invalid OLE automation date: {days}");
+        }
+        let time_of_day = (days.fract().abs() * 86_400_000_000.0).round() as i64;
+        let micros = days.trunc() as i64 * 86_400_000_000 + time_of_day;
+        Self::from_unix_micros(micros + OLE_EPOCH_OFFSET * 1_000_000)
+    }
+
+    /// converts a FAT/DOS date and time, which are given in local time with a
+    /// resolution of two seconds
+    pub fn from_fat(date: u16, time: u16, src_tz: &Tz, policy: DstPolicy) -> Result<Self> {
+        let local_ts = NaiveDate::from_ymd_opt(
+            1980 + (date >> 9) as i32,
+            ((date >> 5) & 0x0f) as u32,
+            (date & 0x1f) as u32,
+        )
+        .and_then(|d| {
+            d.and_hms_opt(
+                (time >> 11) as u32,
+                ((time >> 5) & 0x3f) as u32,
+                (time & 0x1f) as u32 * 2,
+            )
+        })
+        .ok_or_else(|| anyhow!("invalid FAT date/time: {date:#06x} {time:#06x}"))?;
+        Self::from_local(&local_ts, src_tz, policy)
+    }
+
+    /// parses an RFC-3339 string or an ISO-8601 string without offset, which is
+    /// interpreted as UTC
+    ///
+    /// ```
+    /// use es4forensics::Timestamp;
+    ///
+    /// let ts1 = Timestamp::from_iso8601("2019-12-23T10:15:11.5+01:00").unwrap();
+    /// let ts2 = Timestamp::from_iso8601("2019-12-23 09:15:11.5").unwrap();
+    /// assert_eq!(ts1, ts2);
+    /// ```
+    pub fn from_iso8601(value: &str) -> Result<Self> {
+        if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+            return Ok(ts.into());
+        }
+        ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .map(|ts| ts.and_utc().into())
+            .ok_or_else(|| anyhow!("invalid ISO-8601 timestamp: '{value}'"))
     }
 }
+
+/// seconds between 1601-01-01 and 1970-01-01
+const WINDOWS_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+/// seconds between 1904-01-01 and 1970-01-01
+const HFS_EPOCH_OFFSET: i64 = 2_082_844_800;
+
+/// seconds between 1970-01-01 and 2001-01-01
+const COCOA_EPOCH_OFFSET: i64 = 978_307_200;
+
+/// seconds between 1899-12-30 and 1970-01-01, negated
+const OLE_EPOCH_OFFSET: i64 = -2_209_161_600;
+
+/// range of valid OLE automation dates (0100-01-01 to 9999-12-31)
+const OLE_MIN_DAYS: f64 = -657_434.0;
+const OLE_MAX_DAYS: f64 = 2_958_466.0;
@@ -0,0 +1,100 @@
+use serde_json::{json, Number, Value};
+
+use crate::{
+    timestamp::DATE_FIELDS,
+    utils::json::{get_field, set_field},
+    Timestamp,
+};
+
+/// serializes a document into a canonical string, which is suitable to be hashed:
+///
+///  - object keys are sorted,
+///  - numbers with an integral value are written as integers, so that `1.0` equals `1`,
+///  - the top-level `tags` array is sorted, because the order of tags has no meaning,
+///  - date fields are written as by [`canonical_date`], so that the result does not
+///    depend on the [`crate::TimestampFormat`] of the document
+pub fn canonical_document(value: &Value) -> String {
+    let mut value = value.clone();
+    for field in DATE_FIELDS {
+        let date = match get_field(&value, field) {
+            Some(date) => canonical_date(date),
+            None => continue,
+        };
+        match value.get_mut(field) {
+            Some(v) => *v = date,
+            None => {
+                set_field(&mut value, field, date);
+            }
+        }
+    }
+    if let Some(Value::Array(tags)) = value.get_mut("tags") {
+        tags.sort_by_cached_key(canonical_string);
+    }
+    canonical_string(&value)
+}
+
+/// returns the canonical representation of a date: the number of milliseconds since
+/// the epoch, which is how dates were written before they kept nanoseconds, or an
+/// ISO-8601 string if the date has a sub-millisecond fraction. Values which are no
+/// dates are returned unchanged.
+pub fn canonical_date(value: &Value) -> Value {
+    match Timestamp::try_from(value) {
+        Ok(ts) if ts.as_datetime().timestamp_subsec_nanos() % 1_000_000 == 0 => {
+            json!(ts.timestamp_millis())
+        }
+        Ok(ts) => json!(ts.to_iso8601()),
+        Err(_) => value.clone(),
+    }
+}
+
+/// serializes a JSON value into a string whose object keys are sorted and whose
+/// numbers are normalized, so that the result does not depend on the order in
+/// which the keys were inserted or on the type which was used to store a number
+pub fn canonical_string(value: &Value) -> String {
+    let mut result = String::new();
+    write_canonical(value, &mut result);
+    result
+}
+
+fn write_canonical(value: &Value, result: &mut String) {
+    match value {
+        Value::Object(m) => {
+            let mut keys: Vec<&String> = m.keys().collect();
+            keys.sort();
+            result.push('{');
+            for (idx, key) in keys.into_iter().enumerate() {
+                if idx > 0 {
+                    result.push(',');
+                }
+                result.push_str(&Value::String(key.clone()).to_string());
+                result.push(':');
+                write_canonical(&m[key], result);
+            }
+            result.push('}');
+        }
+        Value::Array(a) => {
+            result.push('[');
+            for (idx, v) in a.iter().enumerate() {
+                if idx > 0 {
+                    result.push(',');
+                }
+                write_canonical(v, result);
+            }
+            result.push(']');
+        }
+        Value::Number(n) => result.push_str(&normalized_number(n)),
+        v => result.push_str(&v.to_string()),
+    }
+}
+
+fn normalized_number(n: &Number) -> String {
+    if n.is_f64() {
+        if let Some(f) = n.as_f64() {
+            // every float with an integral value below 2^63 fits into an i64
+            if f.fract() == 0.0 && f.abs() < (i64::MAX as f64) {
+                return (f as i64).to_string();
+            }
+        }
+    }
+    n.to_string()
+}
@@ -10,3 +10,48 @@ pub fn add_to_json(value: &Value, ident: &str, s: Value) -> Value {
         v => v.clone(),
     }
 }
+
+/// returns the value of a (possibly nested) field, such as `file.path`
+pub fn get_field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if let Some(v) = value.get(path) {
+        return Some(v);
+    }
+    path.split('.').try_fold(value, |v, key| v.get(key))
+}
+
+/// sets a (possibly nested) field, such as `log.file.path`, and creates missing
+/// parent objects. Returns `false` if a parent exists, but is not an object.
+pub fn set_field(value: &mut Value, path: &str, field_value: Value) -> bool {
+    let mut current = value;
+    let mut keys = path.split('.').peekable();
+    while let Some(key) = keys.next() {
+        let map = match current {
+            Value::Object(m) => m,
+            _ => return false,
+        };
+        if keys.peek().is_none() {
+            map.insert(key.to_owned(), field_value);
+            return true;
+        }
+        current = map
+            .entry(key.to_owned())
+            .or_insert_with(|| Value::Object(Default::default()));
+    }
+    false
+}
+
+/// removes a (possibly nested) field, such as `log.file.path`, and all parent
+/// objects which become empty by this. Returns the removed value, if any.
+pub fn remove_field(value: &mut Value, path: &str) -> Option<Value> {
+    let map = value.as_object_mut()?;
+    if let Some(v) = map.remove(path) {
+        return Some(v);
+    }
+    let (key, rest) = path.split_once('.')?;
+    let child = map.get_mut(key)?;
+    let removed = remove_field(child, rest)?;
+    if child.as_object().map(|m| m.is_empty()).unwrap_or(false) {
+        map.remove(key);
+    }
+    Some(removed)
+}
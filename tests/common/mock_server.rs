@@ -0,0 +1,12 @@
+use es4forensics::{IndexBuilder, Protocol, WithHost};
+use mockito::Server;
+
+/// creates an [`IndexBuilder`] which connects to a mock server instead of elasticsearch
+pub fn builder_for(server: &Server, index_name: &str) -> IndexBuilder {
+    let address = server.host_with_port();
+    let (host, port) = address.split_once(':').unwrap();
+    IndexBuilder::with_name(index_name.to_string())
+        .with_host(host)
+        .with_port(port.parse().unwrap())
+        .with_protocol(Protocol::Http)
+}
@@ -1 +1,3 @@
-pub mod credentials;
\ No newline at end of file
+#[allow(dead_code)]
+pub mod credentials;
+pub mod mock_server;
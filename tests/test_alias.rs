@@ -0,0 +1,67 @@
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+mod common;
+use common::mock_server::builder_for;
+
+#[tokio::test]
+async fn test_create_index_with_aliases() {
+    let mut server = Server::new_async().await;
+    let exists = server
+        .mock("HEAD", "/dc01")
+        .with_status(404)
+        .create_async()
+        .await;
+    let create = server
+        .mock("PUT", "/dc01")
+        .match_body(Matcher::PartialJson(json!({
+            "aliases": {
+                "case-2024-017": {"is_write_index": false},
+                "case-2024-017-import": {"is_write_index": true}
+            }
+        })))
+        .with_status(200)
+        .with_body(r#"{"acknowledged": true}"#)
+        .create_async()
+        .await;
+
+    builder_for(&server, "dc01")
+        .with_alias("case-2024-017".to_string())
+        .with_write_alias("case-2024-017-import".to_string())
+        .create_index()
+        .await
+        .unwrap();
+
+    exists.assert_async().await;
+    create.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_attach_and_detach_alias() {
+    let mut server = Server::new_async().await;
+    let attach = server
+        .mock("POST", "/_aliases")
+        .match_body(Matcher::Json(json!({
+            "actions": [{"add": {"index": "dc01", "alias": "case-2024-017", "is_write_index": true}}]
+        })))
+        .with_status(200)
+        .with_body(r#"{"acknowledged": true}"#)
+        .create_async()
+        .await;
+    let detach = server
+        .mock("POST", "/_aliases")
+        .match_body(Matcher::Json(json!({
+            "actions": [{"remove": {"index": "dc01", "alias": "case-2024-017"}}]
+        })))
+        .with_status(200)
+        .with_body(r#"{"acknowledged": true}"#)
+        .create_async()
+        .await;
+
+    let builder = builder_for(&server, "dc01");
+    builder.attach_to_alias("case-2024-017", true).await.unwrap();
+    builder.detach_from_alias("case-2024-017").await.unwrap();
+
+    attach.assert_async().await;
+    detach.assert_async().await;
+}
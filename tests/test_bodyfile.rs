@@ -9,62 +9,86 @@ pub fn test_bodyfile_single() {
     let values: Vec<Value> = pfile.into_values().collect();
     let actual = json!(values);
     let expected = json!([{
-    "@timestamp":1577092511000_u64,
+    "@timestamp":"2019-12-23T09:15:11Z",
     "ecs":{"version":"8.4"},
     "message": "/Users/Administrator ($FILE_NAME)",
     "tags": ["bodyfile"],
     "file":{
-        "accessed":1577092511000_u64,
-        "ctime":1577092511000_u64,
+        "accessed":"2019-12-23T09:15:11Z",
+        "ctime":"2019-12-23T09:15:11Z",
         "gid":0,
         "inode":"93552-48-2",
-        "mtime":1577092511000_u64,
+        "mtime":"2019-12-23T09:15:11Z",
         "path":"/Users/Administrator ($FILE_NAME)",
         "name":"Administrator ($FILE_NAME)",
         "directory":"/Users",
-        "mode": "",
+        "mode": "d/drwxrwxrwx",
         "size":92,
         "uid":0,
-        //"macb_long": ["modified", "accessed", "changed"],
-        //"macb_short": "mac."
+        "macb_long": ["modified", "accessed", "changed"],
+        "macb_short": "mac."
         }
     }]);
     assert_json_eq!(actual, expected);
 }
 
-//#[test]
+#[test]
 pub fn test_bodyfile_multiple() {
     let str_line = "0|/Users/Administrator ($FILE_NAME)|93552-48-2|d/drwxrwxrwx|0|0|92|1577092511|1577092511|1577092511|1577092512";
     let pfile: PosixFile = str_line.try_into().unwrap();
     let values: Vec<Value> = pfile.into_values().collect();
     let actual = json!(values);
-    let expected = json!([{
-    "@timestamp":1577092511000_u64,
-    "ecs":{"version":"8.4"},
-    "file":{
-        "accessed":1577092511000_u64,
-        "ctime":1577092511000_u64,
-        "created":1577092512000_u64,
+    let file = |macb_short: &str, macb_long: Value| json!({
+        "accessed":"2019-12-23T09:15:11Z",
+        "ctime":"2019-12-23T09:15:11Z",
+        "created":"2019-12-23T09:15:12Z",
         "gid":0,
         "inode":"93552-48-2",
-        "mtime":1577092511000_u64,
+        "mtime":"2019-12-23T09:15:11Z",
         "path":"/Users/Administrator ($FILE_NAME)",
+        "name":"Administrator ($FILE_NAME)",
+        "directory":"/Users",
+        "mode": "d/drwxrwxrwx",
         "size":92,
-        "uid":0}
+        "uid":0,
+        "macb_short": macb_short,
+        "macb_long": macb_long
+    });
+    let expected = json!([{
+        "@timestamp":"2019-12-23T09:15:11Z",
+        "ecs":{"version":"8.4"},
+        "message": "/Users/Administrator ($FILE_NAME)",
+        "tags": ["bodyfile"],
+        "file": file("mac.", json!(["modified", "accessed", "changed"]))
     },
     {
-        "@timestamp":1577092512000_u64,
-        "ecs":{"version":"1.0.0"},
-        "file":{
-            "accessed":1577092511000_u64,
-            "ctime":1577092511000_u64,
-            "created":1577092512000_u64,
-            "gid":0,
-            "inode":"93552-48-2",
-            "mtime":1577092511000_u64,
-            "path":"/Users/Administrator ($FILE_NAME)",
-            "size":92,
-            "uid":0}
+        "@timestamp":"2019-12-23T09:15:12Z",
+        "ecs":{"version":"8.4"},
+        "message": "/Users/Administrator ($FILE_NAME)",
+        "tags": ["bodyfile"],
+        "file": file("...b", json!(["created"]))
     }]);
     assert_json_eq!(actual, expected);
 }
+
+#[test]
+pub fn test_bodyfile_deterministic_output() {
+    let str_line = "0|/Users/Administrator ($FILE_NAME)|93552-48-2|d/drwxrwxrwx|0|0|92|1577092511|1577092512|1577092511|-1";
+    let convert = || -> Vec<String> {
+        let pfile: PosixFile = str_line.try_into().unwrap();
+        pfile.into_values().map(|v| v.to_string()).collect()
+    };
+    let actual = convert();
+    assert_eq!(actual, convert());
+    assert_eq!(actual.len(), 2);
+    assert_eq!(
+        actual[0],
+        concat!(
+            r#"{"@timestamp":"2019-12-23T09:15:11Z","message":"/Users/Administrator ($FILE_NAME)","tags":["bodyfile"],"#,
+            r#""ecs":{"version":"8.4"},"file":{"mtime":"2019-12-23T09:15:12Z","accessed":"2019-12-23T09:15:11Z","ctime":"2019-12-23T09:15:11Z","#,
+            r#""directory":"/Users","gid":0,"uid":0,"inode":"93552-48-2","mode":"d/drwxrwxrwx","macb_short":".ac.","#,
+            r#""macb_long":["accessed","changed"],"name":"Administrator ($FILE_NAME)","#,
+            r#""path":"/Users/Administrator ($FILE_NAME)","size":92}}"#
+        )
+    );
+}
@@ -0,0 +1,80 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+/// returns the error message of a failed run, without the usage which follows it
+fn error_message(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    stderr.split("Usage:").next().unwrap().to_owned()
+}
+
+/// runs the binary with `args`, passing `input` on stdin
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_es4forensics"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_index_and_password_are_required() {
+    let output = run(&["list-indices"], "");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = error_message(&output);
+    assert!(stderr.contains("--index <INDEX_NAME>"));
+    assert!(stderr.contains("--password <PASSWORD>"));
+
+    let output = run(&["--index", "dc01", "inspect"], "");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = error_message(&output);
+    assert!(!stderr.contains("--index <INDEX_NAME>"));
+    assert!(stderr.contains("--password <PASSWORD>"));
+}
+
+#[test]
+fn test_convert_does_not_need_elasticsearch() {
+    let output = run(&["convert"], "{\"@timestamp\": 1577092511000, \"message\": \"a\"}\n");
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(document["message"], "a");
+}
+
+#[test]
+fn test_convert_multiline_l2tcsv() {
+    let input = concat!(
+        "date,time,timezone,MACB,source,sourcetype,type,user,host,short,desc,version,filename,inode,notes,format,extra\n",
+        "12/23/2019,09:15:12,UTC,.A..,REG,Registry Key,Last Written,jdoe,-,short,\"first line\n",
+        "second line\",2,-,-,-,winreg/default,-\n",
+    );
+    let output = run(&["convert", "--format", "l2tcsv"], input);
+    assert!(output.status.success());
+    let documents: Vec<serde_json::Value> = serde_json::Deserializer::from_slice(&output.stdout)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0]["message"], "first line\nsecond line");
+}
+
+#[test]
+fn test_convert_skips_empty_timestamps_in_local_time() {
+    let input = concat!(
+        "0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|0|0|-1|0\n",
+        "0|/tmp/b|2|r/rrwxrwxrwx|0|0|92|1577092511|-1|-1|-1\n",
+    );
+    let args = ["convert", "--format", "bodyfile", "--timezone", "Europe/Berlin", "--skip-empty-timestamps"];
+    let output = run(&args, input);
+    assert!(output.status.success());
+    let documents: Vec<serde_json::Value> = serde_json::Deserializer::from_slice(&output.stdout)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0]["file"]["path"], "/tmp/b");
+}
@@ -0,0 +1,108 @@
+use std::io::Write;
+
+use es4forensics::{ClockCorrection, ClockOffset, IdStrategy, InputFormat, TimelineParser};
+use serde_json::json;
+
+fn offset(s: &str) -> ClockOffset {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_clock_offset() {
+    assert_eq!(offset("+00:03:12").duration().num_seconds(), 192);
+    assert_eq!(offset("00:03:12").duration().num_seconds(), 192);
+    assert_eq!(offset("-01:00:00").duration().num_seconds(), -3600);
+    assert_eq!(offset("-25:00:00.25").to_string(), "-25:00:00.250");
+    assert_eq!(offset("+00:00:00.000000001").to_string(), "+00:00:00.000000001");
+    for invalid in ["", "3:12", "+00:60:00", "+00:00:60", "+aa:00:00", "+00:00:12:00"] {
+        assert!(invalid.parse::<ClockOffset>().is_err(), "'{invalid}' should be rejected");
+    }
+}
+
+#[test]
+fn test_apply() {
+    let correction = ClockCorrection::default().with_offset(offset("-00:03:12"));
+    let mut document = json!({
+        "@timestamp": "2019-12-23T09:15:11.123456789Z",
+        "message": "test",
+        "file": {"path": "/tmp/a", "mtime": 1577092511000_i64, "accessed": "invalid"}
+    });
+    correction.apply(&mut document);
+    assert_eq!(
+        document,
+        json!({
+            "@timestamp": "2019-12-23T09:11:59.123456789Z",
+            "message": "test",
+            "file": {"path": "/tmp/a", "mtime": 1577092319000_i64, "accessed": "invalid"},
+            "forensics": {
+                "clock_correction": {
+                    "offset": "-00:03:12",
+                    "original": {
+                        "@timestamp": "2019-12-23T09:15:11.123456789Z",
+                        "file": {"mtime": 1577092511000_i64}
+                    }
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn test_host_offsets() {
+    let path = std::env::temp_dir().join(format!("es4forensics-clock-offsets-{}.json", std::process::id()));
+    std::fs::File::create(&path)
+        .unwrap()
+        .write_all(br#"{"DC01": "+01:00:00"}"#)
+        .unwrap();
+    let correction = ClockCorrection::default()
+        .with_offset(offset("+00:00:10"))
+        .with_host_offsets_from(&path)
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let doc = |host: &str| json!({"@timestamp": "2019-12-23T09:15:11Z", "host": {"name": host}});
+    assert_eq!(correction.offset_of(&doc("dc01")), Some(offset("+01:00:00")));
+    assert_eq!(correction.offset_of(&doc("ws01")), Some(offset("+00:00:10")));
+    assert_eq!(ClockCorrection::default().offset_of(&doc("ws01")), None);
+
+    let mut document = doc("dc01");
+    correction.apply(&mut document);
+    assert_eq!(document["@timestamp"], json!("2019-12-23T10:15:11Z"));
+}
+
+#[test]
+fn test_parser() {
+    let mut parser = TimelineParser::new(InputFormat::Bodyfile)
+        .with_clock_correction(ClockCorrection::default().with_offset(offset("+00:00:01")));
+    let values = parser
+        .parse_line("0|/tmp/a|4711|r/rrwxrwxrwx|0|0|92|1577092511|1577092511|-1|-1")
+        .unwrap();
+    assert_eq!(values[0]["@timestamp"], json!("2019-12-23T09:15:12Z"));
+    assert_eq!(values[0]["file"]["mtime"], json!("2019-12-23T09:15:12Z"));
+    assert_eq!(values[0]["forensics"]["clock_correction"]["offset"], json!("+00:00:01"));
+}
+
+#[test]
+fn test_content_hash_of_corrected_documents() {
+    let line = "0|/tmp/a|4711|r/rrwxrwxrwx|0|0|92|1577092511|1577092512|-1|-1";
+    let id = |mut parser: TimelineParser| {
+        let values = parser.parse_line(line).unwrap();
+        IdStrategy::ContentHash.document_id(&values[0]).unwrap().unwrap()
+    };
+    let uncorrected = id(TimelineParser::new(InputFormat::Bodyfile));
+
+    // the original timestamps are hashed, so the offset does not change the id
+    let correction = ClockCorrection::default().with_offset(offset("-00:03:12"));
+    let parser = TimelineParser::new(InputFormat::Bodyfile).with_clock_correction(correction);
+    assert_eq!(id(parser), uncorrected);
+
+    let correction = ClockCorrection::default().with_offset(offset("+01:00:00"));
+    let parser = TimelineParser::new(InputFormat::Bodyfile).with_clock_correction(correction);
+    assert_eq!(id(parser), uncorrected);
+
+    // other fields are still hashed
+    let other = TimelineParser::new(InputFormat::Bodyfile)
+        .parse_line(&line.replace("/tmp/a", "/tmp/b"))
+        .unwrap();
+    assert_ne!(IdStrategy::ContentHash.document_id(&other[0]).unwrap().unwrap(), uncorrected);
+}
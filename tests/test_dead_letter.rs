@@ -0,0 +1,116 @@
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use es4forensics::IdStrategy;
+use mockito::{Matcher, Server};
+use serde_json::{json, Value};
+
+mod common;
+use common::mock_server::builder_for;
+
+/// collects everything written to it, so that the test can inspect it after it has been moved
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+async fn server_with_keyword_mapping() -> mockito::ServerGuard {
+    let mut server = Server::new_async().await;
+    server
+        .mock("HEAD", "/dc01")
+        .with_status(200)
+        .create_async()
+        .await;
+    server
+        .mock("GET", "/dc01/_mapping")
+        .with_status(200)
+        .with_body(
+            json!({"dc01": {"mappings": {"properties": {
+                "event": {"properties": {"code": {"type": "long"}, "action": {"type": "keyword"}}}
+            }}}})
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn test_conflicting_documents_are_rejected() {
+    let mut server = server_with_keyword_mapping().await;
+    let bulk = server
+        .mock("POST", "/dc01/_bulk")
+        .match_body(Matcher::Regex(r#""action":"4711""#.to_string()))
+        .with_status(200)
+        .with_body(r#"{"took": 1, "errors": false, "items": []}"#)
+        .create_async()
+        .await;
+
+    let dead_letter = SharedBuffer::default();
+    let mut index = builder_for(&server, "dc01").connect().await.unwrap();
+    index.set_dead_letter(Box::new(dead_letter.clone()));
+    index
+        .add_bulk_document(json!({"event": {"code": 4624, "action": 4711}}))
+        .await
+        .unwrap();
+    index
+        .add_bulk_document(json!({"event": {"code": "4624-a"}}))
+        .await
+        .unwrap();
+    index.flush().await.unwrap();
+    bulk.assert_async().await;
+
+    assert_eq!(index.rejected_documents(), 1);
+    let rejected: Value = serde_json::from_slice(&dead_letter.0.lock().unwrap()).unwrap();
+    assert_eq!(
+        rejected,
+        json!({
+            "reasons": ["field 'event.code' is mapped as 'long', but the document contains \"4624-a\""],
+            "document": {"event": {"code": "4624-a"}}
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_document_id_does_not_depend_on_mapping() {
+    let mut server = server_with_keyword_mapping().await;
+    let document = json!({"event": {"action": 4711}});
+    let id = IdStrategy::ContentHash.document_id(&document).unwrap().unwrap();
+
+    // the document is coerced, but its id is the one of the original document
+    let bulk = server
+        .mock("POST", "/dc01/_bulk")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(format!(r#""_id":"{id}""#)),
+            Matcher::Regex(r#""action":"4711""#.to_string()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"took": 1, "errors": false, "items": []}"#)
+        .create_async()
+        .await;
+
+    let mut index = builder_for(&server, "dc01").connect().await.unwrap();
+    index.add_bulk_document(document).await.unwrap();
+    index.flush().await.unwrap();
+    bulk.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_rejecting_without_dead_letter_fails() {
+    let server = server_with_keyword_mapping().await;
+    let mut index = builder_for(&server, "dc01").connect().await.unwrap();
+    assert!(index
+        .add_bulk_document(json!({"event": {"code": "4624-a"}}))
+        .await
+        .is_err());
+}
@@ -0,0 +1,123 @@
+use es4forensics::{BulkMode, IdStrategy, InputFormat, TimelineParser, TimestampFormat};
+use serde_json::json;
+
+#[test]
+pub fn test_content_hash() {
+    let doc1 = json!({"message": "foo", "file": {"path": "/tmp/a", "size": 1}});
+    let doc2 = json!({"file": {"size": 1, "path": "/tmp/a"}, "message": "foo"});
+    let doc3 = json!({"file": {"size": 2, "path": "/tmp/a"}, "message": "foo"});
+    let strategy = IdStrategy::ContentHash;
+    let id1 = strategy.document_id(&doc1).unwrap().unwrap();
+    assert_eq!(id1, strategy.document_id(&doc2).unwrap().unwrap());
+    assert_ne!(id1, strategy.document_id(&doc3).unwrap().unwrap());
+}
+
+#[test]
+pub fn test_field_hash() {
+    let doc1 = json!({"@timestamp": 1577092511000_u64, "file": {"path": "/tmp/a", "size": 1}});
+    let doc2 = json!({"@timestamp": 1577092511000_u64, "file": {"path": "/tmp/a", "size": 2}});
+    let doc3 = json!({"@timestamp": 1577092512000_u64, "file": {"path": "/tmp/a", "size": 1}});
+    let strategy = IdStrategy::FieldHash(vec!["@timestamp".to_string(), "file.path".to_string()]);
+    let id1 = strategy.document_id(&doc1).unwrap();
+    assert_eq!(id1, strategy.document_id(&doc2).unwrap());
+    assert_ne!(id1, strategy.document_id(&doc3).unwrap());
+}
+
+#[test]
+pub fn test_auto_and_provided_ids() {
+    let doc = json!({"event": {"sequence": "4711"}, "record_id": 42});
+    assert_eq!(IdStrategy::Auto.document_id(&doc).unwrap(), None);
+    assert_eq!(
+        IdStrategy::FromField("event.sequence".to_string()).document_id(&doc).unwrap(),
+        Some("4711".to_string())
+    );
+    assert_eq!(
+        IdStrategy::FromField("record_id".to_string()).document_id(&doc).unwrap(),
+        Some("42".to_string())
+    );
+    assert!(IdStrategy::FromField("missing".to_string()).document_id(&doc).is_err());
+}
+
+#[test]
+pub fn test_content_hash_normalization() {
+    let doc1 = json!({"@timestamp": 1577092511000_u64, "size": 92, "tags": ["bodyfile", "fls"]});
+    let doc2 = json!({"@timestamp": 1577092511000.0, "size": 92.0, "tags": ["fls", "bodyfile"]});
+    let doc3 = json!({"@timestamp": 1577092511000_u64, "size": 92.5, "tags": ["fls", "bodyfile"]});
+    let strategy = IdStrategy::ContentHash;
+    let id1 = strategy.document_id(&doc1).unwrap();
+    assert_eq!(id1, strategy.document_id(&doc2).unwrap());
+    assert_ne!(id1, strategy.document_id(&doc3).unwrap());
+}
+
+/// document ids must never change between versions of es4forensics, because
+/// otherwise reimporting the same data would create duplicates
+#[test]
+pub fn test_content_hash_is_stable() {
+    let doc = json!({
+        "@timestamp": 1577092511000_u64,
+        "ecs": {"version": "8.4"},
+        "message": "/Users/Administrator ($FILE_NAME)",
+        "tags": ["fls", "bodyfile"],
+        "file": {
+            "inode": "93552-48-2",
+            "mtime": 1577092511000_u64,
+            "path": "/Users/Administrator ($FILE_NAME)",
+            "size": 92
+        }
+    });
+    assert_eq!(
+        IdStrategy::ContentHash.document_id(&doc).unwrap().unwrap(),
+        "CoOYyLBvjp_rBcIrey4zbIK4mR4-nraQhXmUwmzLV3s"
+    );
+    assert_eq!(
+        IdStrategy::FieldHash(vec!["@timestamp".to_string(), "file.inode".to_string()])
+            .document_id(&doc)
+            .unwrap()
+            .unwrap(),
+        "i1qybm662c6BK1s01znhF7gPQGiyISwvZh8U-dfyP54"
+    );
+}
+
+/// ids of generated documents must not depend on `--timestamp-format`, and must not
+/// change between versions of es4forensics
+#[test]
+pub fn test_content_hash_of_parsed_documents_is_stable() {
+    let inputs = [
+        (
+            InputFormat::Bodyfile,
+            "0|/Users/Administrator ($FILE_NAME)|93552-48-2|d/d-wx-wx-wx|0|0|92|1577092511|1577092511|1577092511|-1",
+            "9S4fekfAXfQyizQXkhv-0JKwvQC-PRuk8vO2IubtcJI",
+        ),
+        (
+            InputFormat::L2tcsv,
+            "12/23/2019,09:15:11,UTC,M...,FILE,NTFS MFT,Modification Time,-,dc01,short,desc,2,/x,1,-,mft,-",
+            "qXdPakMfl6xwfXpUheF2E4xZYwBlZcwSygSpPdkRohw",
+        ),
+    ];
+    for (format, line, expected) in inputs {
+        for timestamp_format in [TimestampFormat::Iso8601, TimestampFormat::EpochMillis] {
+            let mut parser = TimelineParser::new(format.clone()).with_timestamp_format(timestamp_format);
+            let document = parser.parse_line(line).unwrap().remove(0);
+            let id = IdStrategy::ContentHash.document_id(&document).unwrap().unwrap();
+            assert_eq!(id, expected, "{} {timestamp_format:?}", format.parser_name());
+        }
+    }
+}
+
+#[test]
+pub fn test_content_hash_keeps_sub_millisecond_dates() {
+    let doc1 = json!({"@timestamp": "2019-12-23T09:15:11Z", "message": "a"});
+    let doc2 = json!({"@timestamp": 1577092511000_u64, "message": "a"});
+    let doc3 = json!({"@timestamp": "2019-12-23T09:15:11.000000001Z", "message": "a"});
+    let strategy = IdStrategy::ContentHash;
+    let id1 = strategy.document_id(&doc1).unwrap();
+    assert_eq!(id1, strategy.document_id(&doc2).unwrap());
+    assert_ne!(id1, strategy.document_id(&doc3).unwrap());
+}
+
+#[test]
+pub fn test_update_requires_ids() {
+    assert!(BulkMode::Update.check_id_strategy(&IdStrategy::Auto).is_err());
+    assert!(BulkMode::Update.check_id_strategy(&IdStrategy::ContentHash).is_ok());
+    assert!(BulkMode::Create.check_id_strategy(&IdStrategy::Auto).is_ok());
+}
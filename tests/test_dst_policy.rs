@@ -0,0 +1,78 @@
+use chrono_tz::Europe::Berlin;
+use es4forensics::{
+    AmbiguousTime, DstAdjustment, DstPolicy, ImportStatistics, InputFormat, NonexistentTime,
+    TimelineParser, Timestamp,
+};
+use serde_json::json;
+
+/// 2019-10-27 02:30:00, which occurs twice in Europe/Berlin
+const AMBIGUOUS: i64 = 1572143400;
+
+/// 2019-03-31 02:30:00, which does not exist in Europe/Berlin
+const NONEXISTENT: i64 = 1553999400;
+
+#[test]
+fn test_ambiguous_time() {
+    let resolve = |ambiguous| {
+        let policy = DstPolicy::default().with_ambiguous(ambiguous);
+        Timestamp::try_from((AMBIGUOUS, &Berlin, policy))
+    };
+
+    let earliest = resolve(AmbiguousTime::Earliest).unwrap();
+    assert_eq!(earliest.to_iso8601(), "2019-10-27T00:30:00Z");
+    assert_eq!(earliest.dst_adjustment(), Some(DstAdjustment::Ambiguous));
+
+    let latest = resolve(AmbiguousTime::Latest).unwrap();
+    assert_eq!(latest.to_iso8601(), "2019-10-27T01:30:00Z");
+    assert_eq!(latest.dst_adjustment(), Some(DstAdjustment::Ambiguous));
+
+    assert!(resolve(AmbiguousTime::Error).is_err());
+}
+
+#[test]
+fn test_nonexistent_time() {
+    assert!(Timestamp::try_from((NONEXISTENT, &Berlin)).is_err());
+
+    let policy = DstPolicy::default().with_nonexistent(NonexistentTime::ShiftForward);
+    let shifted = Timestamp::try_from((NONEXISTENT, &Berlin, policy)).unwrap();
+    assert_eq!(shifted.to_iso8601(), "2019-03-31T01:30:00Z");
+    assert_eq!(shifted.dst_adjustment(), Some(DstAdjustment::Shifted));
+
+    let regular = Timestamp::try_from((NONEXISTENT + 3600, &Berlin, policy)).unwrap();
+    assert_eq!(regular, shifted);
+    assert_eq!(regular.dst_adjustment(), None);
+}
+
+#[test]
+fn test_tagged_documents() {
+    let policy = DstPolicy::default().with_nonexistent(NonexistentTime::ShiftForward);
+    let mut parser = TimelineParser::new(InputFormat::Bodyfile)
+        .with_timezone(Berlin)
+        .with_dst_policy(policy);
+    let line = format!("0|/tmp/a|4711|r/rrwxrwxrwx|0|0|92|{AMBIGUOUS}|{NONEXISTENT}|1577092511|-1");
+    let values = parser.parse_line(&line).unwrap();
+    assert_eq!(values.len(), 3);
+
+    for value in values.iter() {
+        // every document contains all timestamps of the file, so all of them are tagged
+        let mut tags: Vec<&str> = value["tags"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|t| t.as_str())
+            .collect();
+        assert_eq!(tags[0], "bodyfile");
+        tags.sort();
+        assert_eq!(tags, ["bodyfile", "timestamp_ambiguous", "timestamp_shifted"]);
+    }
+
+    // the line is counted once, although it yields three tagged documents
+    let mut statistics = ImportStatistics::default();
+    statistics.add_documents(&values);
+    assert_eq!(statistics.documents, 3);
+    assert_eq!(statistics.ambiguous_lines, 1);
+    assert_eq!(statistics.shifted_lines, 1);
+
+    let values = parser.parse_line("0|/tmp/a|4711|r/rrwxrwxrwx|0|0|92|1577092511|-1|-1|-1").unwrap();
+    assert_eq!(values[0]["tags"], json!(["bodyfile"]));
+}
@@ -0,0 +1,29 @@
+use es4forensics::{DstPolicy, EcsBuilder, Host, Timestamp};
+use serde_json::{json, Value};
+
+#[test]
+pub fn test_tags_are_insertion_ordered() {
+    let ts = Timestamp::try_from((1577092511, &chrono_tz::Tz::UTC)).unwrap();
+    let builder = EcsBuilder::new("test".to_string(), ts)
+        .with_additional_tag("fls")
+        .with_additional_tag("bodyfile")
+        .with_additional_tag("fls")
+        .with_host(Host::from(&json!("sample-host")))
+        .unwrap();
+    let (_, value): (Timestamp, Value) = builder.into();
+    assert_eq!(
+        value.to_string(),
+        r#"{"@timestamp":"2019-12-23T09:15:11Z","message":"test","tags":["fls","bodyfile"],"ecs":{"version":"8.4"},"host":{"name":"sample-host"}}"#
+    );
+}
+
+#[test]
+pub fn test_timestamp_tags_follow_other_tags() {
+    // 2019-10-27 02:30:00, which occurs twice in Europe/Berlin
+    let ts = Timestamp::try_from((1572143400, &chrono_tz::Europe::Berlin, DstPolicy::default())).unwrap();
+    let builder = EcsBuilder::new("test".to_string(), ts.clone())
+        .with_timestamp_tags(&ts)
+        .with_additional_tag("bodyfile");
+    let (_, value): (Timestamp, Value) = builder.into();
+    assert_eq!(value["tags"], json!(["bodyfile", "timestamp_ambiguous"]));
+}
@@ -0,0 +1,81 @@
+use es4forensics::{objects::PosixFile, ExportFormat, TimelineObject, TimelineWriter};
+use serde_json::Value;
+
+fn export(format: ExportFormat, lines: &[&str]) -> String {
+    let mut output = Vec::new();
+    {
+        let mut writer = TimelineWriter::new(format, &mut output).unwrap();
+        for line in lines {
+            let pfile: PosixFile = (*line).try_into().unwrap();
+            let documents: Vec<Value> = pfile.into_values().collect();
+            for document in documents {
+                writer.write_document(&document).unwrap();
+            }
+        }
+        writer.flush().unwrap();
+    }
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+pub fn test_export_bodyfile() {
+    let actual = export(
+        ExportFormat::Bodyfile,
+        &["0|/Users/Administrator ($FILE_NAME)|93552-48-2|d/drwxrwxrwx|0|0|92|1577092511|1577092512|1577092511|-1"],
+    );
+    assert_eq!(
+        actual,
+        "0|/Users/Administrator ($FILE_NAME)|93552-48-2|d/drwxrwxrwx|0|0|92|1577092511|1577092512|1577092511|-1\n"
+    );
+}
+
+#[test]
+pub fn test_export_csv() {
+    let actual = export(
+        ExportFormat::Csv,
+        &["0|/tmp/a,b|4711|r/rrwxrwxrwx|1000|1000|92|1577092511|1577092512|1577092511|-1"],
+    );
+    let expected = concat!(
+        "Date,Size,Type,Mode,UID,GID,Meta,File Name\n",
+        "Mon Dec 23 2019 09:15:11,92,.ac.,r/rrwxrwxrwx,1000,1000,4711,\"/tmp/a,b\"\n",
+        "Mon Dec 23 2019 09:15:12,92,m...,r/rrwxrwxrwx,1000,1000,4711,\"/tmp/a,b\"\n",
+    );
+    assert_eq!(actual, expected);
+}
+
+#[test]
+pub fn test_export_table() {
+    let actual = export(
+        ExportFormat::Table,
+        &["0|/tmp/a|4711|r/rrwxrwxrwx|1000|1000|92|1577092511|1577092511|1577092511|-1"],
+    );
+    assert_eq!(
+        actual,
+        "2019-12-23T09:15:11.000Z  -                 bodyfile          /tmp/a\n"
+    );
+}
+
+#[test]
+pub fn test_export_sorted_bodyfile() {
+    let lines = [
+        "0|/tmp/a|4711|r/rrwxrwxrwx|1000|1000|92|1577092511|1577092513|1577092515|-1",
+        "0|/tmp/b|4712|r/rrwxrwxrwx|1000|1000|92|1577092512|1577092514|1577092516|-1",
+    ];
+    let mut documents: Vec<Value> = lines
+        .iter()
+        .flat_map(|line| PosixFile::try_from(*line).unwrap().into_values())
+        .collect();
+    documents.sort_by_key(|d| d["@timestamp"].as_str().unwrap().to_owned());
+    assert_eq!(documents.len(), 6);
+
+    let mut output = Vec::new();
+    {
+        let mut writer = TimelineWriter::new(ExportFormat::Bodyfile, &mut output).unwrap();
+        for document in documents.iter() {
+            writer.write_document(document).unwrap();
+        }
+        writer.flush().unwrap();
+    }
+    let expected: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+}
@@ -0,0 +1,108 @@
+use std::{fs::File, io::Read};
+
+use es4forensics::{EvidenceFile, ImportAudit, ImportStatistics, InputDigest};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+mod common;
+use common::mock_server::builder_for;
+
+fn sample_audit() -> ImportAudit {
+    let evidence = EvidenceFile::from_path("tests/data/sample.bodyfile.json").unwrap();
+    let mut statistics = ImportStatistics::default();
+    statistics.lines = 12;
+    statistics.parse_errors = 1;
+    statistics.documents = 11;
+    let mut audit = ImportAudit::start("dc01".to_string())
+        .with_input_file(evidence)
+        .with_timezone("Europe/Berlin".to_string());
+    audit.finish(&statistics, 2, 1);
+    audit
+}
+
+#[test]
+fn test_evidence_file() {
+    let evidence = EvidenceFile::from_path("tests/data/sample.bodyfile.json").unwrap();
+    assert!(evidence.path.ends_with("tests/data/sample.bodyfile.json"));
+    assert_eq!(evidence.size, Some(21454));
+    assert_eq!(
+        evidence.sha256.as_deref(),
+        Some("8940c1460129ddf8e626098bde12fd0ed92b12a69f0baa878a5d700b41a6f076")
+    );
+
+    let stdin = EvidenceFile::from_path("-").unwrap();
+    assert_eq!(stdin.size, None);
+    assert_eq!(stdin.sha256, None);
+}
+
+#[test]
+fn test_evidence_file_from_digest() {
+    let digest = InputDigest::default();
+    let file = File::open("tests/data/sample.bodyfile.json").unwrap();
+    let mut content = Vec::new();
+    digest.reader(file).read_to_end(&mut content).unwrap();
+
+    let evidence = EvidenceFile::from_digest("tests/data/sample.bodyfile.json", &digest);
+    let expected = EvidenceFile::from_path("tests/data/sample.bodyfile.json").unwrap();
+    assert_eq!(evidence.path, expected.path);
+    assert_eq!(evidence.size, expected.size);
+    assert_eq!(evidence.sha256, expected.sha256);
+
+    let incomplete = EvidenceFile::without_hash("tests/data/sample.bodyfile.json");
+    assert_eq!(incomplete.path, expected.path);
+    assert_eq!(incomplete.size, None);
+    assert_eq!(incomplete.sha256, None);
+}
+
+#[test]
+fn test_audit_document() {
+    let document = sample_audit().to_document();
+    assert_eq!(document["event"]["action"], json!("import"));
+    assert_eq!(document["event"]["outcome"], json!("success"));
+    assert!(document["event"]["end"].is_string());
+    assert_eq!(document["agent"]["version"], json!(env!("CARGO_PKG_VERSION")));
+    assert_eq!(document["forensics"]["status"], json!("completed"));
+    assert_eq!(document["forensics"]["timezone"], json!("Europe/Berlin"));
+    assert_eq!(
+        document["forensics"]["input_files"][0]["hash"]["sha256"],
+        json!("8940c1460129ddf8e626098bde12fd0ed92b12a69f0baa878a5d700b41a6f076")
+    );
+    assert_eq!(
+        document["forensics"]["counts"],
+        json!({
+            "lines": 12,
+            "parse_errors": 1,
+            "documents": 11,
+            "filtered": 0,
+            "ambiguous_lines": 0,
+            "shifted_lines": 0,
+            "rejected": 2,
+            "failed": 1,
+            "imported": 8
+        })
+    );
+
+    let mut audit = sample_audit();
+    audit.fail("connection refused".to_string());
+    let document = audit.to_document();
+    assert_eq!(document["event"]["outcome"], json!("failure"));
+    assert_eq!(document["event"]["reason"], json!("connection refused"));
+    assert_eq!(document["forensics"]["status"], json!("failed"));
+}
+
+#[tokio::test]
+async fn test_write_audit() {
+    let mut server = Server::new_async().await;
+    let audit_index = server
+        .mock("POST", "/dc01-audit/_doc")
+        .match_body(Matcher::PartialJson(json!({"forensics": {"index": "dc01"}})))
+        .with_status(201)
+        .with_body(r#"{"result": "created"}"#)
+        .create_async()
+        .await;
+
+    let builder = builder_for(&server, "dc01");
+    assert_eq!(builder.audit_index_name(), "dc01-audit");
+    builder.write_audit(&sample_audit()).await.unwrap();
+    audit_index.assert_async().await;
+}
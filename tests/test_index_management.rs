@@ -0,0 +1,246 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use chrono::{TimeZone, Utc};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+mod common;
+use common::mock_server::builder_for;
+
+#[tokio::test]
+async fn test_list_indices() {
+    let mut server = Server::new_async().await;
+    let cat = server
+        .mock("GET", Matcher::Regex("^/_cat/indices/case-".to_string()))
+        .match_query(Matcher::UrlEncoded("format".into(), "json".into()))
+        .with_status(200)
+        .with_body(
+            json!([
+                {"index": "case-dc02", "status": "close", "docs.count": null, "store.size": null},
+                {"index": "case-dc01", "status": "open", "docs.count": "4711", "store.size": "1.2mb"},
+                {"index": "case-dc03", "status": "open", "docs.count": "0", "store.size": "225b"}
+            ])
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    // the time ranges of all open indices are queried at once
+    let time_ranges = server
+        .mock("POST", Matcher::Regex("^/case-dc01(,|%2C)case-dc03/_search".to_string()))
+        .match_body(Matcher::PartialJson(json!({
+            "size": 0,
+            "aggs": {
+                "indices": {
+                    "terms": {"field": "_index", "size": 2},
+                    "aggs": {
+                        "first": {"min": {"field": "@timestamp"}},
+                        "last": {"max": {"field": "@timestamp"}}
+                    }
+                }
+            }
+        })))
+        .with_status(200)
+        .with_body(
+            json!({
+                "hits": {"total": {"value": 4711}, "hits": []},
+                "aggregations": {"indices": {"buckets": [{
+                    "key": "case-dc01",
+                    "doc_count": 4711,
+                    "first": {"value": 1577092511000.0, "value_as_string": "2019-12-23T09:15:11.000Z"},
+                    "last": {"value": 1577092513000.0, "value_as_string": "2019-12-23T09:15:13.000Z"}
+                }]}}
+            })
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let indices = builder_for(&server, "case-*").list_indices().await.unwrap();
+    cat.assert_async().await;
+    time_ranges.assert_async().await;
+
+    assert_eq!(indices.len(), 3);
+    assert_eq!(indices[0].name, "case-dc01");
+    assert_eq!(indices[0].status, "open");
+    assert_eq!(indices[0].docs_count, 4711);
+    assert_eq!(indices[0].store_size, "1.2mb");
+    assert_eq!(
+        indices[0].first_timestamp,
+        Some(Utc.with_ymd_and_hms(2019, 12, 23, 9, 15, 11).unwrap())
+    );
+    assert_eq!(
+        indices[0].last_timestamp,
+        Some(Utc.with_ymd_and_hms(2019, 12, 23, 9, 15, 13).unwrap())
+    );
+
+    // the time range of closed indices cannot be queried
+    assert_eq!(indices[1].name, "case-dc02");
+    assert_eq!(indices[1].status, "close");
+    assert_eq!(indices[1].docs_count, 0);
+    assert_eq!(indices[1].first_timestamp, None);
+    assert_eq!(indices[1].last_timestamp, None);
+
+    // empty indices have no bucket
+    assert_eq!(indices[2].name, "case-dc03");
+    assert_eq!(indices[2].first_timestamp, None);
+}
+
+#[tokio::test]
+async fn test_list_empty_index() {
+    let mut server = Server::new_async().await;
+    let _cat = server
+        .mock("GET", "/_cat/indices/dc01")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(r#"[{"index": "dc01", "status": "open", "docs.count": "0", "store.size": "225b"}]"#)
+        .create_async()
+        .await;
+    let _time_range = server
+        .mock("POST", "/dc01/_search")
+        .with_status(200)
+        .with_body(r#"{"aggregations": {"indices": {"buckets": []}}}"#)
+        .create_async()
+        .await;
+
+    let indices = builder_for(&server, "dc01").list_indices().await.unwrap();
+    assert_eq!(indices.len(), 1);
+    assert_eq!(indices[0].docs_count, 0);
+    assert_eq!(indices[0].first_timestamp, None);
+    assert_eq!(indices[0].last_timestamp, None);
+}
+
+#[tokio::test]
+async fn test_list_indices_fails_on_error() {
+    let mut server = Server::new_async().await;
+    let _cat = server
+        .mock("GET", Matcher::Any)
+        .with_status(403)
+        .with_body(r#"{"error": {"type": "security_exception"}, "status": 403}"#)
+        .create_async()
+        .await;
+
+    assert!(builder_for(&server, "dc01").list_indices().await.is_err());
+}
+
+#[tokio::test]
+async fn test_inspect() {
+    let mut server = Server::new_async().await;
+    let mapping = json!({"properties": {"@timestamp": {"type": "date"}}});
+
+    // 'dc01' is an alias, so the mapping is reported for the concrete index
+    let _mapping = server
+        .mock("GET", "/dc01/_mapping")
+        .with_status(200)
+        .with_body(json!({"dc01-000001": {"mappings": mapping}}).to_string())
+        .create_async()
+        .await;
+    let _sample = server
+        .mock("POST", "/dc01/_search")
+        .match_body(Matcher::Json(json!({"size": 1})))
+        .with_status(200)
+        .with_body(
+            json!({"hits": {"hits": [{"_id": "1", "_source": {"message": "/etc/passwd"}}]}})
+                .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let builder = builder_for(&server, "dc01");
+    assert_eq!(builder.mapping().await.unwrap(), mapping);
+    assert_eq!(
+        builder.sample_document().await.unwrap(),
+        Some(json!({"message": "/etc/passwd"}))
+    );
+}
+
+#[tokio::test]
+async fn test_inspect_empty_index() {
+    let mut server = Server::new_async().await;
+    let _sample = server
+        .mock("POST", "/dc01/_search")
+        .with_status(200)
+        .with_body(r#"{"hits": {"hits": []}}"#)
+        .create_async()
+        .await;
+
+    assert_eq!(builder_for(&server, "dc01").sample_document().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_delete_index() {
+    let mut server = Server::new_async().await;
+    let delete = server
+        .mock("DELETE", "/dc01")
+        .with_status(200)
+        .with_body(r#"{"acknowledged": true}"#)
+        .create_async()
+        .await;
+
+    builder_for(&server, "dc01").delete_index().await.unwrap();
+    delete.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_delete_index_refused_by_elasticsearch() {
+    let mut server = Server::new_async().await;
+    let delete = server
+        .mock("DELETE", "/dc01")
+        .with_status(403)
+        .with_body(r#"{"error": {"type": "security_exception"}, "status": 403}"#)
+        .create_async()
+        .await;
+
+    assert!(builder_for(&server, "dc01").delete_index().await.is_err());
+    delete.assert_async().await;
+}
+
+/// runs `es4forensics delete-index` against `server` and answers the confirmation with `answer`
+fn delete_index_with_answer(server: &Server, answer: &str) -> bool {
+    let address = server.host_with_port();
+    let (host, port) = address.split_once(':').unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_es4forensics"))
+        .args(["--index", "dc01", "--password", "secret", "--proto", "http"])
+        .args(["--host", host, "--port", port, "delete-index"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    writeln!(child.stdin.take().unwrap(), "{answer}").unwrap();
+    child.wait().unwrap().success()
+}
+
+#[test]
+fn test_delete_index_requires_confirmation() {
+    let mut server = Server::new();
+    let _exists = server.mock("HEAD", "/dc01").with_status(200).create();
+    let delete = server
+        .mock("DELETE", "/dc01")
+        .with_status(200)
+        .with_body(r#"{"acknowledged": true}"#)
+        .expect(0)
+        .create();
+
+    // the deletion is aborted, which is not an error
+    assert!(delete_index_with_answer(&server, "dc02"));
+    delete.assert();
+}
+
+#[test]
+fn test_delete_index_after_confirmation() {
+    let mut server = Server::new();
+    let _exists = server.mock("HEAD", "/dc01").with_status(200).create();
+    let delete = server
+        .mock("DELETE", "/dc01")
+        .with_status(200)
+        .with_body(r#"{"acknowledged": true}"#)
+        .expect(1)
+        .create();
+
+    assert!(delete_index_with_answer(&server, "dc01"));
+    delete.assert();
+}
@@ -0,0 +1,135 @@
+use std::process::{Command, Stdio};
+
+use es4forensics::TimelineQuery;
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+mod common;
+use common::mock_server::builder_for;
+
+fn hit(id: usize, sort: u64) -> serde_json::Value {
+    json!({"_id": id.to_string(), "_source": {"message": format!("document {id}")}, "sort": [sort, id]})
+}
+
+#[tokio::test]
+async fn test_read_pages() {
+    let mut server = Server::new_async().await;
+    let open = server
+        .mock("POST", "/dc01/_pit")
+        .match_query(Matcher::UrlEncoded("keep_alive".into(), "5m".into()))
+        .with_status(200)
+        .with_body(r#"{"id": "pit-1"}"#)
+        .create_async()
+        .await;
+    let first_page = server
+        .mock("POST", "/_search")
+        .match_body(Matcher::PartialJson(json!({
+            "size": 2,
+            "pit": {"id": "pit-1"},
+            "sort": [{"@timestamp": "asc"}, {"_shard_doc": "asc"}]
+        })))
+        .with_status(200)
+        .with_body(json!({"pit_id": "pit-2", "hits": {"hits": [hit(1, 1000), hit(2, 2000)]}}).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let second_page = server
+        .mock("POST", "/_search")
+        .match_body(Matcher::PartialJson(json!({
+            "pit": {"id": "pit-2"},
+            "search_after": [2000, 2]
+        })))
+        .with_status(200)
+        .with_body(json!({"pit_id": "pit-2", "hits": {"hits": [hit(3, 3000)]}}).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let close = server
+        .mock("DELETE", "/_pit")
+        .match_body(Matcher::Json(json!({"id": "pit-2"})))
+        .with_status(200)
+        .with_body(r#"{"succeeded": true, "num_freed": 1}"#)
+        .create_async()
+        .await;
+
+    let builder = builder_for(&server, "dc01");
+    let mut reader = builder
+        .open_reader(&TimelineQuery::default())
+        .await
+        .unwrap()
+        .with_page_size(2);
+
+    let page = reader.next_page().await.unwrap().unwrap();
+    assert_eq!(page, vec![json!({"message": "document 1"}), json!({"message": "document 2"})]);
+    let page = reader.next_page().await.unwrap().unwrap();
+    assert_eq!(page, vec![json!({"message": "document 3"})]);
+
+    // the last page was not full, so there is no need to ask for another one
+    assert!(reader.next_page().await.unwrap().is_none());
+    reader.close().await.unwrap();
+
+    open.assert_async().await;
+    first_page.assert_async().await;
+    second_page.assert_async().await;
+    close.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_read_empty_page() {
+    let mut server = Server::new_async().await;
+    let _open = server
+        .mock("POST", "/dc01/_pit")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(r#"{"id": "pit-1"}"#)
+        .create_async()
+        .await;
+    let search = server
+        .mock("POST", "/_search")
+        .with_status(200)
+        .with_body(r#"{"pit_id": "pit-1", "hits": {"hits": []}}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let builder = builder_for(&server, "dc01");
+    let mut reader = builder.open_reader(&TimelineQuery::default()).await.unwrap();
+    assert!(reader.next_page().await.unwrap().is_none());
+    assert!(reader.next_page().await.unwrap().is_none());
+    search.assert_async().await;
+}
+
+#[test]
+fn test_export_closes_pit_on_error() {
+    let mut server = Server::new();
+    let _open = server
+        .mock("POST", "/dc01/_pit")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(r#"{"id": "pit-1"}"#)
+        .create();
+    let _search = server
+        .mock("POST", "/_search")
+        .with_status(500)
+        .with_body(r#"{"error": {"type": "search_phase_execution_exception"}, "status": 500}"#)
+        .create();
+    let close = server
+        .mock("DELETE", "/_pit")
+        .match_body(Matcher::Json(json!({"id": "pit-1"})))
+        .with_status(200)
+        .with_body(r#"{"succeeded": true, "num_freed": 1}"#)
+        .expect(1)
+        .create();
+
+    let address = server.host_with_port();
+    let (host, port) = address.split_once(':').unwrap();
+    let status = Command::new(env!("CARGO_BIN_EXE_es4forensics"))
+        .args(["--index", "dc01", "--password", "secret", "--proto", "http"])
+        .args(["--host", host, "--port", port, "export"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(!status.success());
+    close.assert();
+}
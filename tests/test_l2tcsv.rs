@@ -0,0 +1,101 @@
+use es4forensics::{objects::{L2tCsvEvent, Macb}, InputFormat, TimelineObject, TimelineParser};
+use serde_json::{json, Value};
+
+const HEADER: &str = "date,time,timezone,MACB,source,sourcetype,type,user,host,short,desc,version,filename,inode,notes,format,extra";
+const MFT_LINE: &str = r#"12/23/2019,10:15:11,Europe/Berlin,M..B,FILE,NTFS MFT,Creation Time,-,DC01,/Users/Administrator,NTFS:\Users\Administrator Type: directory,2,/Users/Administrator,93552,-,mft,"file_reference: 93552-48; is_allocated: True""#;
+const REG_LINE: &str = "12/23/2019,09:15:12,UTC,.A..,REG,Registry Key,Last Written,jdoe,-,short,-,2,-,-,-,winreg/default,-";
+
+#[test]
+fn test_l2tcsv_event() {
+    let event = L2tCsvEvent::try_from(MFT_LINE).unwrap();
+    let values: Vec<Value> = event.into_values().collect();
+    assert_eq!(values.len(), 1);
+    let value = &values[0];
+
+    // the timezone column must be honored
+    assert_eq!(value["@timestamp"], json!("2019-12-23T09:15:11Z"));
+    assert_eq!(value["message"], json!(r"NTFS:\Users\Administrator Type: directory"));
+    assert_eq!(value["tags"], json!(["l2tcsv"]));
+    assert_eq!(value["host"]["name"], json!("DC01"));
+    assert_eq!(value["event"]["action"], json!("Creation Time"));
+    assert_eq!(value["event"]["module"], json!("mft"));
+    assert_eq!(value["file"]["path"], json!("/Users/Administrator"));
+    assert_eq!(value["file"]["inode"], json!("93552"));
+    assert_eq!(value["file"]["macb_short"], json!("m..b"));
+    assert_eq!(value["file"]["macb_long"], json!(["modified", "created"]));
+    assert_eq!(value["plaso"]["source"], json!("FILE"));
+    assert_eq!(
+        value["plaso"]["extra"],
+        json!({"file_reference": "93552-48", "is_allocated": "True"})
+    );
+    assert!(value.get("user").is_none());
+}
+
+#[test]
+fn test_l2tcsv_without_file() {
+    let values: Vec<Value> = L2tCsvEvent::try_from(REG_LINE).unwrap().into_values().collect();
+    let value = &values[0];
+    assert_eq!(value["@timestamp"], json!("2019-12-23T09:15:12Z"));
+    assert_eq!(value["message"], json!("short"));
+    assert_eq!(value["user"]["name"], json!("jdoe"));
+    assert!(value.get("file").is_none());
+    assert!(value.get("host").is_none());
+}
+
+#[test]
+fn test_l2tcsv_errors() {
+    assert!(L2tCsvEvent::try_from("12/23/2019,09:15:12,UTC").is_err());
+    assert!(L2tCsvEvent::try_from(REG_LINE.replace("UTC", "Mars/Olympus_Mons").as_str()).is_err());
+    assert!(L2tCsvEvent::try_from(REG_LINE.replace("12/23/2019", "2019-12-23").as_str()).is_err());
+}
+
+#[test]
+fn test_parser() {
+    let mut parser = TimelineParser::new(InputFormat::L2tcsv);
+    assert!(parser.parse_line(HEADER).unwrap().is_empty());
+    assert_eq!(parser.parse_line(REG_LINE).unwrap().len(), 1);
+
+    let mut parser = TimelineParser::new(InputFormat::Bodyfile).with_timezone(chrono_tz::Europe::Berlin);
+    let values = parser
+        .parse_line("0|/tmp/a|4711|r/rrwxrwxrwx|0|0|92|1577092511|1577092511|1577092511|-1")
+        .unwrap();
+    assert_eq!(values.len(), 1);
+    assert_eq!(values[0]["@timestamp"], json!("2019-12-23T08:15:11Z"));
+    assert_eq!(InputFormat::Bodyfile.parser_name(), "bodyfile");
+}
+
+#[test]
+fn test_parse_multiline_records() {
+    let input = format!(
+        "{HEADER}\n{}\n{REG_LINE}\n",
+        REG_LINE.replace(",short,-,", ",short,\"first line\nsecond line\",")
+    );
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(input.as_bytes());
+    let mut parser = TimelineParser::new(InputFormat::L2tcsv);
+    let values: Vec<Value> = reader
+        .records()
+        .flat_map(|record| parser.parse_csv_record(&record.unwrap()).unwrap())
+        .collect();
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0]["message"], json!("first line\nsecond line"));
+    assert_eq!(values[1]["message"], json!("short"));
+
+    assert!(TimelineParser::new(InputFormat::Json)
+        .parse_csv_record(&csv::StringRecord::from(vec!["a"]))
+        .is_err());
+}
+
+#[test]
+fn test_macb_from_str() {
+    assert_eq!(
+        Macb::from("m.c."),
+        Macb { modified: true, accessed: false, changed: true, created: false }
+    );
+    assert_eq!(
+        Macb::from(".A.B"),
+        Macb { modified: false, accessed: true, changed: false, created: true }
+    );
+    assert_eq!(Macb::from(""), Macb::default());
+}
@@ -0,0 +1,63 @@
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+mod common;
+use common::mock_server::builder_for;
+
+#[tokio::test]
+async fn test_set_lifecycle_policy() {
+    let mut server = Server::new_async().await;
+    let settings = server
+        .mock("PUT", "/dc01/_settings")
+        .match_body(Matcher::Json(json!({"index.lifecycle.name": "closed-cases"})))
+        .with_status(200)
+        .with_body(r#"{"acknowledged": true}"#)
+        .create_async()
+        .await;
+
+    builder_for(&server, "dc01")
+        .set_lifecycle_policy("closed-cases")
+        .await
+        .unwrap();
+    settings.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_snapshot_and_restore() {
+    let mut server = Server::new_async().await;
+    let snapshot = server
+        .mock("POST", "/_snapshot/archive/case-2024-017")
+        .match_query(Matcher::UrlEncoded("wait_for_completion".into(), "true".into()))
+        .match_body(Matcher::PartialJson(json!({
+            "indices": "case-2024-017",
+            "include_global_state": false
+        })))
+        .with_status(200)
+        .with_body(r#"{"snapshot": {"snapshot": "case-2024-017", "shards": {"total": 2, "failed": 0, "successful": 2}}}"#)
+        .create_async()
+        .await;
+    let restore = server
+        .mock("POST", "/_snapshot/archive/case-2024-017/_restore")
+        .match_query(Matcher::UrlEncoded("wait_for_completion".into(), "true".into()))
+        .match_body(Matcher::Json(json!({
+            "indices": "case-2024-017",
+            "include_global_state": false,
+            "include_aliases": false,
+            "rename_pattern": "(.+)",
+            "rename_replacement": "restored-$1"
+        })))
+        .with_status(200)
+        .with_body(r#"{"snapshot": {"snapshot": "case-2024-017", "shards": {"total": 2, "failed": 1, "successful": 1}}}"#)
+        .create_async()
+        .await;
+
+    let builder = builder_for(&server, "case-2024-017");
+    builder.create_snapshot("archive", "case-2024-017").await.unwrap();
+    assert!(builder
+        .restore_snapshot("archive", "case-2024-017", Some("restored-$1"))
+        .await
+        .is_err());
+
+    snapshot.assert_async().await;
+    restore.assert_async().await;
+}
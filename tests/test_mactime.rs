@@ -0,0 +1,54 @@
+use es4forensics::{objects::MactimeEvent, InputFormat, TimelineObject, TimelineParser};
+use serde_json::{json, Value};
+
+const HEADER: &str = "Date,Size,Type,Mode,UID,GID,Meta,File Name";
+const LINE: &str = "Mon Dec 23 2019 10:15:11,92,m.cb,r/rrwxr-xr-x,1000,100,4711-128-1,/home/jdoe/a,b.txt";
+const ISO_LINE: &str = "2019-12-23T09:15:11Z,0,.a..,d/drwxr-xr-x,0,0,2,/etc";
+
+#[test]
+fn test_mactime_event() {
+    let event = MactimeEvent::try_from((LINE, &chrono_tz::Europe::Berlin)).unwrap();
+    let values: Vec<Value> = event.into_values().collect();
+    assert_eq!(values.len(), 1);
+    let value = &values[0];
+
+    assert_eq!(value["@timestamp"], json!("2019-12-23T09:15:11Z"));
+    assert_eq!(value["message"], json!("/home/jdoe/a,b.txt"));
+    assert_eq!(value["tags"], json!(["mactime"]));
+
+    let file = &value["file"];
+    assert_eq!(file["path"], json!("/home/jdoe/a,b.txt"));
+    assert_eq!(file["inode"], json!("4711-128-1"));
+    assert_eq!(file["mode"], json!("r/rrwxr-xr-x"));
+    assert_eq!(file["uid"], json!(1000));
+    assert_eq!(file["gid"], json!(100));
+    assert_eq!(file["size"], json!(92));
+    assert_eq!(file["macb_short"], json!("m.cb"));
+    assert_eq!(file["macb_long"], json!(["modified", "changed", "created"]));
+    assert_eq!(file["mtime"], json!("2019-12-23T09:15:11Z"));
+    assert_eq!(file["created"], json!("2019-12-23T09:15:11Z"));
+    assert!(file.get("accessed").is_none());
+}
+
+#[test]
+fn test_mactime_iso_date() {
+    let values: Vec<Value> = MactimeEvent::try_from(ISO_LINE).unwrap().into_values().collect();
+    assert_eq!(values[0]["@timestamp"], json!("2019-12-23T09:15:11Z"));
+    assert_eq!(values[0]["file"]["accessed"], json!("2019-12-23T09:15:11Z"));
+    assert_eq!(values[0]["file"]["macb_long"], json!(["accessed"]));
+}
+
+#[test]
+fn test_mactime_errors() {
+    assert!(MactimeEvent::try_from("Mon Dec 23 2019 10:15:11,92,m.cb").is_err());
+    assert!(MactimeEvent::try_from(LINE.replace("Mon Dec", "Mon Foo").as_str()).is_err());
+    assert!(MactimeEvent::try_from(LINE.replace(",1000,", ",root,").as_str()).is_err());
+}
+
+#[test]
+fn test_parser() {
+    let mut parser = TimelineParser::new(InputFormat::Mactime).with_timezone(chrono_tz::Europe::Berlin);
+    assert!(parser.parse_line(HEADER).unwrap().is_empty());
+    let values = parser.parse_line(LINE).unwrap();
+    assert_eq!(values[0]["@timestamp"], json!("2019-12-23T09:15:11Z"));
+}
@@ -0,0 +1,82 @@
+use es4forensics::{FieldConflict, ImportStatistics, IndexBuilder, MappingChecker};
+use serde_json::json;
+
+#[test]
+fn test_default_mapping() {
+    let mut checker = MappingChecker::from_mapping(&IndexBuilder::default_mappings());
+    let document = json!({
+        "@timestamp": 1577092511000_i64,
+        "tags": ["bodyfile"],
+        "file": {"path": "/tmp/a", "mtime": "2019-12-23T09:15:11.123456789Z", "macb_short": "m..b"}
+    });
+    assert!(checker.check(&document).is_empty());
+
+    let conflicts = checker.check(&json!({"@timestamp": "12/23/2019", "file": "/tmp/a"}));
+    assert_eq!(
+        conflicts,
+        vec![
+            FieldConflict {
+                field: "@timestamp".to_string(),
+                mapped_type: "date_nanos".to_string(),
+                value: json!("12/23/2019"),
+            },
+            FieldConflict {
+                field: "file".to_string(),
+                mapped_type: "object".to_string(),
+                value: json!("/tmp/a"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_dynamic_fields() {
+    let mut checker = MappingChecker::from_mapping(&json!({}));
+    assert!(checker.check(&json!({"event": {"code": 4624}})).is_empty());
+    assert_eq!(checker.field_type("event"), Some("object"));
+    assert_eq!(checker.field_type("event.code"), Some("long"));
+
+    assert!(checker.check(&json!({"event": {"code": "4625"}})).is_empty());
+    let conflicts = checker.check(&json!({"event.code": ["4624", "4624-a"]}));
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(
+        conflicts[0].to_string(),
+        "field 'event.code' is mapped as 'long', but the document contains \"4624-a\""
+    );
+}
+
+#[test]
+fn test_statistics() {
+    let mut checker = MappingChecker::from_mapping(&json!({
+        "properties": {"source": {"properties": {"ip": {"type": "ip"}}}}
+    }));
+    let mut statistics = ImportStatistics::default();
+    for (id, ip) in [("a", "10.0.0.1"), ("b", "fe80::1"), ("a", "unknown"), ("c", "-")] {
+        statistics.documents += 1;
+        statistics.add_id(id);
+        statistics.add_conflicts(checker.check(&json!({"source": {"ip": ip}})));
+    }
+    assert_eq!(statistics.duplicate_ids, 1);
+    assert_eq!(statistics.conflicting_documents, 2);
+    let (count, first) = &statistics.conflicts["source.ip"];
+    assert_eq!(*count, 2);
+    assert_eq!(first.value, json!("unknown"));
+}
+
+#[test]
+fn test_coerce() {
+    let mut checker = MappingChecker::from_mapping(&json!({
+        "properties": {
+            "event": {"properties": {"code": {"type": "keyword"}, "sequence": {"type": "long"}}}
+        }
+    }));
+    let mut document = json!({"event": {"code": [4624, 4625], "sequence": 17}});
+    assert!(checker.coerce(&mut document).is_empty());
+    assert_eq!(document, json!({"event": {"code": ["4624", "4625"], "sequence": 17}}));
+
+    let mut document = json!({"event": {"code": true, "sequence": "4624-a"}});
+    let conflicts = checker.coerce(&mut document);
+    assert_eq!(document["event"]["code"], json!("true"));
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].field, "event.sequence");
+}
@@ -0,0 +1,36 @@
+use assert_json_diff::assert_json_eq;
+use es4forensics::Pipeline;
+use serde_json::json;
+
+#[test]
+pub fn test_pipeline_with_metadata() {
+    let pipeline = Pipeline::with_id("test_pipeline".to_string())
+        .with_ingest_timestamp()
+        .with_case("2024-017")
+        .with_analyst("jdoe");
+    assert_eq!(pipeline.id(), "test_pipeline");
+    let expected = json!({
+        "description": "created by es4forensics",
+        "processors": [
+            {"set": {"field": "event.ingested", "value": "{{{_ingest.timestamp}}}"}},
+            {"set": {"field": "forensics.case", "value": "2024-017"}},
+            {"set": {"field": "forensics.analyst", "value": "jdoe"}}
+        ]
+    });
+    assert_json_eq!(pipeline.body(), &expected);
+}
+
+#[test]
+pub fn test_pipeline_from_definition() {
+    let definition = json!({
+        "description": "custom pipeline",
+        "processors": [{"lowercase": {"field": "host.name"}}]
+    });
+    let pipeline = Pipeline::from_definition("custom".to_string(), definition.clone())
+        .unwrap()
+        .with_case("2024-017");
+    assert_eq!(pipeline.body()["processors"].as_array().unwrap().len(), 2);
+    assert_eq!(pipeline.body()["description"], definition["description"]);
+
+    assert!(Pipeline::from_definition("invalid".to_string(), json!({})).is_err());
+}
@@ -0,0 +1,95 @@
+use std::io::Write;
+
+use es4forensics::{
+    objects::{PlasoEvent, PlasoMapping},
+    InputFormat, TimelineObject, TimelineParser,
+};
+use serde_json::{json, Value};
+
+fn convert(event: Value, mapping: &PlasoMapping) -> Value {
+    let values: Vec<Value> = PlasoEvent::new(event, mapping).unwrap().into_values().collect();
+    assert_eq!(values.len(), 1);
+    values.into_iter().next().unwrap()
+}
+
+#[test]
+fn test_common_attributes() {
+    let value = convert(
+        json!({
+            "__container_type__": "event",
+            "data_type": "fs:stat",
+            "display_name": "OS:/etc/passwd",
+            "filename": "/etc/passwd",
+            "inode": 4711,
+            "hostname": "web01",
+            "username": "-",
+            "parser": "filestat",
+            "timestamp": 1577092511123456_i64,
+            "timestamp_desc": "Last Access Time",
+            "is_allocated": true
+        }),
+        &PlasoMapping::default(),
+    );
+    assert_eq!(value["@timestamp"], json!("2019-12-23T09:15:11.123456Z"));
+    assert_eq!(value["message"], json!("OS:/etc/passwd"));
+    assert_eq!(value["tags"], json!(["plaso"]));
+    assert_eq!(
+        value["event"],
+        json!({"dataset": "fs:stat", "module": "filestat", "action": "Last Access Time"})
+    );
+    assert_eq!(value["file"], json!({"path": "/etc/passwd", "inode": 4711}));
+    assert_eq!(value["host"]["name"], json!("web01"));
+    assert!(value.get("user").is_none());
+    assert_eq!(
+        value["plaso"],
+        json!({"display_name": "OS:/etc/passwd", "is_allocated": true})
+    );
+}
+
+#[test]
+fn test_data_type_mapping() {
+    let event = json!({
+        "data_type": "windows:evtx:record",
+        "event_identifier": 4624,
+        "computer_name": "DC01",
+        "message": "logon",
+        "timestamp": 1577092511000000_i64,
+        "tag": {"labels": ["login", "plaso"]}
+    });
+    let value = convert(event.clone(), &PlasoMapping::default());
+    assert_eq!(value["event"]["code"], json!(4624));
+    assert_eq!(value["host"]["name"], json!("DC01"));
+    assert_eq!(value["tags"], json!(["plaso", "login"]));
+    assert!(value.get("plaso").is_none());
+
+    let mapping = PlasoMapping::default().with_field("windows:evtx:record", "computer_name", "observer.hostname");
+    let value = convert(event, &mapping);
+    assert_eq!(value["observer"]["hostname"], json!("DC01"));
+    assert!(value.get("host").is_none());
+}
+
+#[test]
+fn test_mapping_file() {
+    let path = std::env::temp_dir().join(format!("es4forensics-plaso-mapping-{}.json", std::process::id()));
+    std::fs::File::create(&path)
+        .unwrap()
+        .write_all(br#"{"windows:lnk:link": {"drive_type": "file.device"}}"#)
+        .unwrap();
+    let mapping = PlasoMapping::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(mapping.ecs_field("windows:lnk:link", "drive_type"), Some("file.device"));
+    assert_eq!(mapping.ecs_field("windows:lnk:link", "filename"), Some("file.path"));
+    assert_eq!(mapping.ecs_field("fs:stat", "drive_type"), None);
+}
+
+#[test]
+fn test_parser() {
+    let mut parser = TimelineParser::new(InputFormat::JsonLine);
+    let values = parser
+        .parse_line(r#"{"data_type": "syslog:line", "pid": 17, "timestamp": 1577092511000000}"#)
+        .unwrap();
+    assert_eq!(values[0]["process"]["pid"], json!(17));
+    assert!(parser.parse_line(r#"{"data_type": "syslog:line"}"#).is_err());
+    assert!(parser.parse_line("[]").is_err());
+}
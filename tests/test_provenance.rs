@@ -0,0 +1,54 @@
+use es4forensics::{EvidenceFile, IdStrategy, Provenance};
+use serde_json::json;
+
+#[test]
+fn test_provenance_fields() {
+    let evidence = EvidenceFile::from_path("tests/data/sample.bodyfile.json").unwrap();
+    let provenance = Provenance::new(&evidence, "json");
+
+    let mut document = json!({"message": "foo", "log": {"level": "info"}});
+    provenance.apply(&mut document, 3, 1024);
+    assert_eq!(document["log"]["level"], json!("info"));
+    assert_eq!(document["log"]["offset"], json!(1024));
+    assert!(document["log"]["file"]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("tests/data/sample.bodyfile.json"));
+    assert_eq!(
+        document["forensics"]["source"],
+        json!({
+            "line": 3,
+            "parser": "json",
+            "sha256": "8940c1460129ddf8e626098bde12fd0ed92b12a69f0baa878a5d700b41a6f076"
+        })
+    );
+}
+
+#[test]
+fn test_provenance_keeps_conflicting_fields() {
+    let provenance = Provenance::new(&EvidenceFile::from_path("-").unwrap(), "json");
+    let mut document = json!({"log": "syslog"});
+    provenance.apply(&mut document, 1, 0);
+    assert_eq!(document["log"], json!("syslog"));
+    assert_eq!(document["forensics"]["source"]["sha256"], json!(null));
+    assert_eq!(document["forensics"]["source"]["line"], json!(1));
+}
+
+#[test]
+fn test_provenance_is_not_hashed() {
+    let document = json!({"message": "foo", "log": {"level": "info"}});
+    let id = IdStrategy::ContentHash.document_id(&document).unwrap();
+
+    // the same record, read from another copy of the evidence at another position
+    for (path, line, offset) in [("tests/data/sample.bodyfile.json", 3, 1024), ("-", 7, 2048)] {
+        let provenance = Provenance::new(&EvidenceFile::from_path(path).unwrap(), "json");
+        let mut imported = document.clone();
+        provenance.apply(&mut imported, line, offset);
+        assert_ne!(imported, document);
+        assert_eq!(IdStrategy::ContentHash.document_id(&imported).unwrap(), id);
+    }
+
+    // other fields are still hashed
+    let other = json!({"message": "foo", "log": {"level": "warn"}});
+    assert_ne!(IdStrategy::ContentHash.document_id(&other).unwrap(), id);
+}
@@ -0,0 +1,135 @@
+use es4forensics::{ClockCorrection, InputFormat, PathPattern, TimelineFilter, TimelineParser, Timestamp};
+use serde_json::json;
+
+fn timestamp(value: &str) -> Timestamp {
+    Timestamp::from_iso8601(value).unwrap()
+}
+
+#[test]
+fn test_time_window() {
+    let filter = TimelineFilter::default()
+        .with_start(timestamp("2019-12-23T09:15:11Z"))
+        .with_end(timestamp("2019-12-23T09:15:12Z"));
+    assert!(filter.matches(&json!({"@timestamp": "2019-12-23T09:15:11Z"})));
+    assert!(filter.matches(&json!({"@timestamp": 1577092512000_i64})));
+    assert!(!filter.matches(&json!({"@timestamp": "2019-12-23T09:15:12.000000001Z"})));
+    assert!(!filter.matches(&json!({"@timestamp": "2019-12-23T09:15:10Z"})));
+    assert!(!filter.matches(&json!({"message": "no timestamp"})));
+    assert!(TimelineFilter::default().matches(&json!({"message": "no timestamp"})));
+}
+
+#[test]
+fn test_paths() {
+    let filter = TimelineFilter::default()
+        .with_include_path(PathPattern::glob("/home/*").unwrap())
+        .with_include_path(PathPattern::regex(r"^/tmp/[^/]+\.sh$").unwrap())
+        .with_exclude_path(PathPattern::glob("/home/*/.cache/*").unwrap());
+    let doc = |path: &str| json!({"@timestamp": 0, "file": {"path": path}});
+    assert!(filter.matches(&doc("/home/jdoe/.bash_history")));
+    assert!(filter.matches(&doc("/tmp/x.sh")));
+    assert!(!filter.matches(&doc("/tmp/a/x.sh")));
+    assert!(!filter.matches(&doc("/home/jdoe/.cache/thumbnails/a.png")));
+    assert!(!filter.matches(&doc("/etc/passwd")));
+    assert!(!filter.matches(&json!({"@timestamp": 0})));
+
+    let filter = TimelineFilter::default().with_exclude_path(PathPattern::glob("/usr/*").unwrap());
+    assert!(filter.matches(&json!({"@timestamp": 0})));
+    assert!(PathPattern::regex("(").is_err());
+    assert!(PathPattern::glob("[").is_err());
+}
+
+fn skip_empty_parser(format: InputFormat) -> TimelineParser {
+    TimelineParser::new(format).with_filter(TimelineFilter::default().with_skip_empty_timestamps(true))
+}
+
+#[test]
+fn test_empty_timestamps() {
+    let mut parser = skip_empty_parser(InputFormat::Bodyfile);
+    let mut values = |line: &str| parser.parse_line(line).unwrap().len();
+    assert_eq!(values("0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|0|0|-1|0"), 0);
+    assert_eq!(values("0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|0|1577092511|-1|-1"), 2);
+    assert_eq!(values("0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|1577092511|-1|-1|-1"), 1);
+    assert_eq!(parser.filtered_documents(), 1);
+
+    // documents are not checked, only the values of the input
+    let filter = TimelineFilter::default().with_skip_empty_timestamps(true);
+    assert!(filter.matches(&json!({"@timestamp": -1000})));
+}
+
+#[test]
+fn test_empty_timestamps_in_local_time() {
+    // 0 in Europe/Berlin is 1969-12-31T23:00:00Z, which is still unset
+    let mut parser = skip_empty_parser(InputFormat::Bodyfile).with_timezone(chrono_tz::Europe::Berlin);
+    assert!(parser.parse_line("0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|0|0|-1|0").unwrap().is_empty());
+    let values = parser
+        .parse_line("0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|0|1577092511|-1|-1")
+        .unwrap();
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0]["@timestamp"], json!("1969-12-31T23:00:00Z"));
+
+    // mactime has written the time 0 in Europe/Berlin
+    let mut parser = skip_empty_parser(InputFormat::Mactime).with_timezone(chrono_tz::Europe::Berlin);
+    assert!(parser
+        .parse_line("Thu Jan 01 1970 01:00:00,92,m...,r/rrwxrwxrwx,0,0,4711,/tmp/a")
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        parser
+            .parse_line("Thu Jan 01 1970 00:00:00,92,m...,r/rrwxrwxrwx,0,0,4711,/tmp/a")
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_empty_timestamps_with_clock_correction() {
+    let correction = ClockCorrection::default().with_offset("+00:03:12".parse().unwrap());
+    let mut parser = skip_empty_parser(InputFormat::Bodyfile).with_clock_correction(correction);
+    assert!(parser.parse_line("0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|0|0|-1|0").unwrap().is_empty());
+}
+
+#[test]
+fn test_parser() {
+    let filter = TimelineFilter::default().with_start(timestamp("2019-12-23T09:15:12Z"));
+    let mut parser = TimelineParser::new(InputFormat::Bodyfile).with_filter(filter);
+    let values = parser
+        .parse_line("0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|1577092511|1577092512|1577092513|-1")
+        .unwrap();
+    assert_eq!(values.len(), 2);
+    assert_eq!(parser.filtered_documents(), 1);
+}
+
+#[test]
+fn test_parser_with_clock_correction_and_paths() {
+    let filter = || {
+        TimelineFilter::default()
+            .with_start(timestamp("2019-12-23T09:15:13Z"))
+            .with_exclude_path(PathPattern::glob("/usr/*").unwrap())
+    };
+    let correction = ClockCorrection::default().with_offset("+00:00:02".parse().unwrap());
+    let mut parser = TimelineParser::new(InputFormat::Bodyfile)
+        .with_clock_correction(correction.clone())
+        .with_filter(filter());
+
+    // the window is applied to the corrected timestamps
+    let values = parser
+        .parse_line("0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|1577092511|1577092510|-1|-1")
+        .unwrap();
+    assert_eq!(values.len(), 1);
+    assert_eq!(values[0]["@timestamp"], json!("2019-12-23T09:15:13Z"));
+    assert!(parser
+        .parse_line("0|/usr/a|1|r/rrwxrwxrwx|0|0|92|1577092511|1577092511|-1|-1")
+        .unwrap()
+        .is_empty());
+    assert_eq!(parser.filtered_documents(), 2);
+
+    // json input is filtered by its values
+    let mut parser = TimelineParser::new(InputFormat::Json)
+        .with_clock_correction(correction)
+        .with_filter(filter());
+    let line = |path: &str| json!({"@timestamp": "2019-12-23T09:15:11Z", "file": {"path": path}}).to_string();
+    assert_eq!(parser.parse_line(&line("/tmp/a")).unwrap().len(), 1);
+    assert!(parser.parse_line(&line("/usr/a")).unwrap().is_empty());
+    assert_eq!(parser.filtered_documents(), 1);
+}
@@ -0,0 +1,44 @@
+use assert_json_diff::assert_json_eq;
+use chrono::Duration;
+use es4forensics::{TimelineQuery, Timestamp};
+use serde_json::json;
+
+#[test]
+pub fn test_empty_query() {
+    assert_json_eq!(TimelineQuery::default().to_query(), json!({"match_all": {}}));
+}
+
+#[test]
+pub fn test_query_around_pivot() {
+    let pivot = Timestamp::try_from((1577092511, &chrono_tz::Tz::UTC)).unwrap();
+    let query = TimelineQuery::default()
+        .with_window_around(&pivot, Duration::minutes(5))
+        .with_hosts(vec!["dc01".to_string()])
+        .with_tags(vec!["bodyfile".to_string(), "fls".to_string()])
+        .with_query_string("file.path:*.exe".to_string());
+    let expected = json!({
+        "bool": {
+            "filter": [
+                {"range": {"@timestamp": {
+                    "format": "strict_date_optional_time_nanos",
+                    "gte": "2019-12-23T09:10:11Z",
+                    "lte": "2019-12-23T09:20:11Z"
+                }}},
+                {"terms": {"host.name": ["dc01"]}},
+                {"term": {"tags": "bodyfile"}},
+                {"term": {"tags": "fls"}},
+                {"query_string": {"query": "file.path:*.exe"}}
+            ]
+        }
+    });
+    assert_json_eq!(query.to_query(), expected);
+}
+
+#[test]
+pub fn test_window_keeps_nanoseconds() {
+    let pivot = Timestamp::from_unix_nanos(1577092511123456789).unwrap();
+    let query = TimelineQuery::default().with_window_around(&pivot, Duration::nanoseconds(1));
+    let range = &query.to_query()["bool"]["filter"][0]["range"]["@timestamp"];
+    assert_eq!(range["gte"], json!("2019-12-23T09:15:11.123456788Z"));
+    assert_eq!(range["lte"], json!("2019-12-23T09:15:11.123456790Z"));
+}
@@ -0,0 +1,92 @@
+use es4forensics::{InputFormat, TimelineParser, TimelineSorter};
+use serde_json::{json, Value};
+
+fn sort(documents: Vec<Value>, buffer_size: usize) -> (Vec<Value>, usize) {
+    let mut sorter = TimelineSorter::new(buffer_size);
+    for document in documents {
+        sorter.add(document).unwrap();
+    }
+    let runs = sorter.runs();
+    let sorted = sorter.into_sorted().unwrap().map(|d| d.unwrap()).collect();
+    (sorted, runs)
+}
+
+#[test]
+fn test_external_sort() {
+    // 1577092511000 = 2019-12-23T09:15:11Z
+    let documents: Vec<Value> = (0..50_i64)
+        .map(|i| json!({"@timestamp": 1577092511000_i64 + (i * 7919) % 50 * 1000, "id": i}))
+        .collect();
+    let (in_memory, runs) = sort(documents.clone(), 100);
+    assert_eq!(runs, 0);
+    let (external, runs) = sort(documents, 7);
+    assert_eq!(runs, 7);
+
+    assert_eq!(in_memory, external);
+    assert_eq!(external.len(), 50);
+    for (i, document) in external.iter().enumerate() {
+        assert_eq!(document["@timestamp"], json!(1577092511000_i64 + i as i64 * 1000));
+    }
+}
+
+#[test]
+fn test_stable_sort() {
+    let documents = vec![
+        json!({"@timestamp": "2019-12-23T09:15:12Z", "id": 0}),
+        json!({"@timestamp": "2019-12-23T09:15:11Z", "id": 1}),
+        json!({"@timestamp": 1577092512000_i64, "id": 2}),
+        json!({"message": "without timestamp", "id": 3}),
+        json!({"@timestamp": "2019-12-23T09:15:11.5Z", "id": 4}),
+        json!({"@timestamp": "2019-12-23T09:15:12.000Z", "id": 5}),
+    ];
+    for buffer_size in [1, 2, 100] {
+        let (sorted, _) = sort(documents.clone(), buffer_size);
+        let ids: Vec<i64> = sorted.iter().map(|d| d["id"].as_i64().unwrap()).collect();
+        assert_eq!(ids, [3, 1, 4, 0, 2, 5], "buffer size {buffer_size}");
+    }
+}
+
+#[test]
+fn test_merge_many_runs() {
+    // 1000 runs of one document, with only 10 distinct timestamps
+    let documents: Vec<Value> = (0..1000_i64)
+        .map(|i| json!({"@timestamp": 1577092511000_i64 + (i * 7) % 10 * 1000, "id": i}))
+        .collect();
+    let mut sorter = TimelineSorter::new(1);
+    for document in documents {
+        sorter.add(document).unwrap();
+        assert!(sorter.runs() < 48);
+    }
+    let sorted: Vec<Value> = sorter.into_sorted().unwrap().map(|d| d.unwrap()).collect();
+    assert_eq!(sorted.len(), 1000);
+    for pair in sorted.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        let (ts_a, ts_b) = (a["@timestamp"].as_i64().unwrap(), b["@timestamp"].as_i64().unwrap());
+        assert!(ts_a < ts_b || (ts_a == ts_b && a["id"].as_i64() < b["id"].as_i64()));
+    }
+}
+
+#[test]
+fn test_merge_formats() {
+    let mut bodyfile = TimelineParser::new(InputFormat::Bodyfile);
+    let mut l2tcsv = TimelineParser::new(InputFormat::Bodyfile).with_format(InputFormat::L2tcsv);
+    let mut sorter = TimelineSorter::new(1);
+    for document in bodyfile
+        .parse_line("0|/tmp/a|1|r/rrwxrwxrwx|0|0|92|1577092511|1577092513|-1|-1")
+        .unwrap()
+        .into_iter()
+        .chain(
+            l2tcsv
+                .parse_line("12/23/2019,09:15:12,UTC,M...,FILE,NTFS MFT,Modification Time,-,dc01,s,d,2,/x,1,-,mft,-")
+                .unwrap(),
+        )
+    {
+        sorter.add(document).unwrap();
+    }
+    let tags: Vec<Value> = sorter
+        .into_sorted()
+        .unwrap()
+        .map(|d| d.unwrap()["tags"][0].clone())
+        .collect();
+    assert_eq!(tags, [json!("bodyfile"), json!("l2tcsv"), json!("bodyfile")]);
+}
@@ -0,0 +1,130 @@
+use chrono::{DateTime, TimeZone, Utc};
+use es4forensics::{
+    ClockCorrection, DstPolicy, IndexBuilder, InputFormat, MappingChecker, TimelineParser,
+    Timestamp, TimestampFormat,
+};
+use serde_json::json;
+
+fn timestamp(nanos: u32) -> Timestamp {
+    Utc.timestamp_opt(1577092511, nanos).unwrap().into()
+}
+
+#[test]
+fn test_iso8601_serialization() {
+    assert_eq!(json!(timestamp(123_456_789)), json!("2019-12-23T09:15:11.123456789Z"));
+    assert_eq!(json!(timestamp(123_000_000)), json!("2019-12-23T09:15:11.123Z"));
+    assert_eq!(json!(timestamp(0)), json!("2019-12-23T09:15:11Z"));
+}
+
+#[test]
+fn test_epoch_millis_format() {
+    let ts = timestamp(123_456_789);
+    assert_eq!(ts.to_value(TimestampFormat::EpochMillis), json!(1577092511123_i64));
+    assert_eq!(ts.to_value(TimestampFormat::Iso8601), json!("2019-12-23T09:15:11.123456789Z"));
+
+    let mut parser = TimelineParser::new(InputFormat::Bodyfile).with_timestamp_format(TimestampFormat::EpochMillis);
+    let values = parser
+        .parse_line("0|/tmp/a|4711|r/rrwxrwxrwx|0|0|92|1577092511|-1|-1|-1")
+        .unwrap();
+    assert_eq!(values[0]["@timestamp"], json!(1577092511000_i64));
+    assert_eq!(values[0]["file"]["accessed"], json!(1577092511000_i64));
+}
+
+#[test]
+fn test_epoch_millis_format_of_corrected_documents() {
+    let mut parser = TimelineParser::new(InputFormat::Bodyfile)
+        .with_clock_correction(ClockCorrection::default().with_offset("+00:00:01".parse().unwrap()))
+        .with_timestamp_format(TimestampFormat::EpochMillis);
+    let values = parser
+        .parse_line("0|/tmp/a|4711|r/rrwxrwxrwx|0|0|92|1577092511|-1|-1|-1")
+        .unwrap();
+    assert_eq!(values[0]["@timestamp"], json!(1577092512000_i64));
+    assert_eq!(
+        values[0]["forensics"]["clock_correction"]["original"]["@timestamp"],
+        json!(1577092511000_i64)
+    );
+}
+
+#[test]
+fn test_timestamp_format_keeps_json_input() {
+    let mut parser = TimelineParser::new(InputFormat::Json).with_timestamp_format(TimestampFormat::EpochMillis);
+    let values = parser
+        .parse_line(r#"{"@timestamp": "2019-12-23T09:15:11Z"}"#)
+        .unwrap();
+    assert_eq!(values[0]["@timestamp"], json!("2019-12-23T09:15:11Z"));
+}
+
+#[test]
+fn test_pre_1970_timestamp() {
+    let line = "07/20/1969,20:17:40,UTC,M...,FILE,OS,Modification Time,-,-,short,desc,2,/moon,-,-,os,-";
+    let mut parser = TimelineParser::new(InputFormat::L2tcsv);
+    let values = parser.parse_line(line).unwrap();
+    assert_eq!(values[0]["@timestamp"], json!("1969-07-20T20:17:40Z"));
+
+    // date_nanos cannot store the date, so the document is rejected
+    let mapping = IndexBuilder::default_mappings();
+    assert_eq!(mapping["properties"]["@timestamp"]["type"], json!("date_nanos"));
+    let mut checker = MappingChecker::from_mapping(&mapping);
+    let conflicts = checker.check(&values[0]);
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].field, "@timestamp");
+    assert_eq!(conflicts[0].mapped_type, "date_nanos");
+    assert!(checker.check(&parser.parse_line(&line.replace("1969", "1970")).unwrap()[0]).is_empty());
+
+    let mut parser = parser.with_timestamp_format(TimestampFormat::EpochMillis);
+    let values = parser.parse_line(line).unwrap();
+    assert_eq!(values[0]["@timestamp"], json!(-14_182_940_000_i64));
+}
+
+#[test]
+fn test_sub_millisecond_precision() {
+    let earlier = timestamp(123_456_000);
+    let later = timestamp(123_456_001);
+    assert!(earlier < later);
+    assert_eq!(earlier.timestamp_millis(), later.timestamp_millis());
+    assert_eq!(later.timestamp_nanos(), Some(1577092511123456001));
+    assert_eq!(
+        later.as_datetime(),
+        &DateTime::parse_from_rfc3339("2019-12-23T09:15:11.123456001Z").unwrap()
+    );
+    assert_eq!(Timestamp::try_from((1577092511, &chrono_tz::UTC)).unwrap(), timestamp(0));
+}
+
+#[test]
+fn test_converters() {
+    let expected = "2019-12-23T09:15:11Z";
+    let iso = |ts: anyhow::Result<Timestamp>| ts.unwrap().to_iso8601();
+
+    assert_eq!(iso(Timestamp::from_unix_millis(1577092511000)), expected);
+    assert_eq!(iso(Timestamp::from_unix_micros(1577092511000000)), expected);
+    assert_eq!(iso(Timestamp::from_unix_nanos(1577092511000000000)), expected);
+    assert_eq!(iso(Timestamp::from_unix_millis(-1)), "1969-12-31T23:59:59.999Z");
+    assert_eq!(iso(Timestamp::from_filetime(132215661110000000)), expected);
+    assert_eq!(iso(Timestamp::from_webkit(13221566111000000)), expected);
+    assert_eq!(iso(Timestamp::from_hfs_plus(3659937311)), expected);
+    assert_eq!(iso(Timestamp::from_cocoa(598785311.0)), expected);
+    assert_eq!(iso(Timestamp::from_cocoa(598785311.25)), "2019-12-23T09:15:11.250Z");
+    assert_eq!(iso(Timestamp::from_ole_automation(43822.38554398148)), expected);
+    assert_eq!(iso(Timestamp::from_ole_automation(-1.25)), "1899-12-29T06:00:00Z");
+    assert_eq!(iso(Timestamp::from_iso8601("2019-12-23T10:15:11+01:00")), expected);
+    assert_eq!(iso(Timestamp::from_iso8601("2019-12-23T09:15:11")), expected);
+
+    // 2019-12-23 10:15:10, local time
+    let date = (39 << 9) | (12 << 5) | 23;
+    let time = (10 << 11) | (15 << 5) | 5;
+    assert_eq!(iso(Timestamp::from_fat(date, time, &chrono_tz::Europe::Berlin, DstPolicy::default())), "2019-12-23T09:15:10Z");
+}
+
+#[test]
+fn test_converter_ranges() {
+    assert!(Timestamp::from_filetime(0).is_err());
+    assert!(Timestamp::from_filetime(u64::MAX).is_err());
+    assert!(Timestamp::from_webkit(0).is_err());
+    assert!(Timestamp::from_hfs_plus(0).is_err());
+    assert!(Timestamp::from_cocoa(f64::NAN).is_err());
+    assert!(Timestamp::from_cocoa(1e300).is_err());
+    assert!(Timestamp::from_ole_automation(1e7).is_err());
+    assert!(Timestamp::from_fat(0, 0, &chrono_tz::UTC, DstPolicy::default()).is_err());
+    assert!(Timestamp::from_fat((39 << 9) | (12 << 5) | 23, 24 << 11, &chrono_tz::UTC, DstPolicy::default()).is_err());
+    assert!(Timestamp::from_iso8601("12/23/2019").is_err());
+}